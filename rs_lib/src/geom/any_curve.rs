@@ -0,0 +1,237 @@
+//! Curves whose dimension is chosen at runtime.
+//!
+//! Algorithms are generic over the dimension `D` of [`Curve<D>`], so that
+//! points are fixed-size vectors on the stack. [`AnyCurve`] has one variant
+//! per supported dimension, and dispatching on it instantiates every algorithm
+//! once per variant. The code size of the WebAssembly module therefore grows
+//! linearly with [`MAX_DIMENSION`], which is why dimensions are capped instead
+//! of falling back to heap-allocated points.
+
+use nalgebra::Point;
+
+use crate::geom::curve::Curve;
+use crate::geom::Dist;
+
+/// Largest dimension of curves given from JavaScript.
+///
+/// This covers 3D flight paths as well as short feature vectors, such as
+/// position, velocity and time. Trajectories of more features should be
+/// reduced to at most this many dimensions first, e.g. by PCA. Raising the
+/// cap requires a variant in [`AnyCurve`] and an arm in each macro below per
+/// added dimension.
+pub const MAX_DIMENSION: usize = 8;
+
+/// Curve whose dimension is only known at runtime, e.g. because its points
+/// were given from JavaScript.
+#[derive(Debug, Clone)]
+pub enum AnyCurve {
+    D1(Curve<1>),
+    D2(Curve<2>),
+    D3(Curve<3>),
+    D4(Curve<4>),
+    D5(Curve<5>),
+    D6(Curve<6>),
+    D7(Curve<7>),
+    D8(Curve<8>),
+}
+
+/// Evaluates `$body` with `$curve` bound to the `Curve<D>` inside the
+/// [`AnyCurve`] `$any`, for whichever dimension `D` it has.
+macro_rules! with_curve {
+    ($any:expr, $curve:ident => $body:expr) => {
+        match $any {
+            $crate::geom::any_curve::AnyCurve::D1($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D2($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D3($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D4($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D5($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D6($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D7($curve) => $body,
+            $crate::geom::any_curve::AnyCurve::D8($curve) => $body,
+        }
+    };
+}
+
+/// Evaluates `$body` with `$curves` bound to an array of references to the
+/// curves inside the two [`AnyCurve`]s `$pair`, wrapped in `Some`, or returns
+/// `None` if their dimensions differ.
+macro_rules! with_curve_pair {
+    ($pair:expr, $curves:ident => $body:expr) => {{
+        use $crate::geom::any_curve::AnyCurve;
+        match $pair {
+            [AnyCurve::D1(c1), AnyCurve::D1(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D2(c1), AnyCurve::D2(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D3(c1), AnyCurve::D3(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D4(c1), AnyCurve::D4(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D5(c1), AnyCurve::D5(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D6(c1), AnyCurve::D6(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D7(c1), AnyCurve::D7(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            [AnyCurve::D8(c1), AnyCurve::D8(c2)] => {
+                let $curves = [c1, c2];
+                Some($body)
+            }
+            _ => None,
+        }
+    }};
+}
+
+pub(crate) use with_curve_pair;
+
+impl Default for AnyCurve {
+    fn default() -> Self {
+        Self::D2(Curve::default())
+    }
+}
+
+impl AnyCurve {
+    /// Creates a curve through `points`, whose dimension is the number of
+    /// coordinates of each point, or 2 if there are no points. Returns `None`
+    /// if the points have different numbers of coordinates, or none or more
+    /// than [`MAX_DIMENSION`].
+    pub fn from_points(points: Vec<Vec<Dist>>) -> Option<Self> {
+        let dimension = points.first().map_or(2, Vec::len);
+        if points.iter().any(|point| point.len() != dimension) {
+            return None;
+        }
+
+        fn curve<const D: usize>(points: &[Vec<Dist>]) -> Curve<D> {
+            Curve::from_points(
+                points
+                    .iter()
+                    .map(|point| Point::from_slice(point))
+                    .collect(),
+            )
+        }
+
+        Some(match dimension {
+            1 => Self::D1(curve(&points)),
+            2 => Self::D2(curve(&points)),
+            3 => Self::D3(curve(&points)),
+            4 => Self::D4(curve(&points)),
+            5 => Self::D5(curve(&points)),
+            6 => Self::D6(curve(&points)),
+            7 => Self::D7(curve(&points)),
+            8 => Self::D8(curve(&points)),
+            _ => return None,
+        })
+    }
+
+    pub fn dimension(&self) -> usize {
+        fn dimension<const D: usize>(_: &Curve<D>) -> usize {
+            D
+        }
+        with_curve!(self, curve => dimension(curve))
+    }
+
+    /// Returns the points of the curve, as their coordinates.
+    pub fn points(&self) -> Vec<Vec<Dist>> {
+        with_curve!(self, curve => curve
+            .points()
+            .iter()
+            .map(|point| point.coords.as_slice().to_vec())
+            .collect())
+    }
+
+    pub fn cumulative_lengths(&self) -> &Vec<Dist> {
+        with_curve!(self, curve => curve.cumulative_lengths())
+    }
+
+    pub fn cumulative_params(&self) -> &Vec<Dist> {
+        with_curve!(self, curve => curve.cumulative_params())
+    }
+
+    pub fn total_param(&self) -> Dist {
+        with_curve!(self, curve => curve.total_param())
+    }
+
+    pub fn segment_param_to_param(&self, segment_idx: usize, t: Dist) -> Dist {
+        with_curve!(self, curve => curve.segment_param_to_param(segment_idx, t))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        with_curve!(self, curve => curve.points().is_empty())
+    }
+
+    /// Returns whether both curves have the same dimension and points.
+    pub fn same_points(&self, other: &Self) -> bool {
+        with_curve_pair!([self, other], curves => {
+            let [curve_1, curve_2] = curves;
+            curve_1.points() == curve_2.points()
+        })
+        .unwrap_or(false)
+    }
+}
+
+impl From<Curve> for AnyCurve {
+    fn from(curve: Curve) -> Self {
+        Self::D2(curve)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::geom::dtw::{dtw, DtwOptions};
+    use crate::geom::frechet::frechet_dist;
+
+    #[test]
+    fn flight_paths() {
+        // Parallel lines one unit apart in z
+        let curve_1 = AnyCurve::from_points(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![2.0, 0.0, 0.0],
+        ])
+        .unwrap();
+        let curve_2 = AnyCurve::from_points(vec![
+            vec![0.0, 0.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![2.0, 0.0, 1.0],
+        ])
+        .unwrap();
+        assert_eq!(curve_1.dimension(), 3);
+        assert_relative_eq!(curve_2.total_param(), 2.0);
+
+        let dist = with_curve_pair!([&curve_1, &curve_2], curves => {
+            frechet_dist(curves)
+        });
+        assert_relative_eq!(dist.unwrap(), 1.0, max_relative = 1e-4);
+
+        // Vertex-based measures see the middle vertex of the second line too
+        let cost = with_curve_pair!([&curve_1, &curve_2], curves => {
+            dtw(curves, &DtwOptions::default()).unwrap().cost
+        });
+        assert_relative_eq!(cost.unwrap(), 2.0 + Dist::sqrt(2.));
+
+        let planar = AnyCurve::from_points(vec![vec![0.0, 0.0]]).unwrap();
+        assert!(with_curve_pair!([&curve_1, &planar], curves => {
+            frechet_dist(curves)
+        })
+        .is_none());
+        assert!(
+            AnyCurve::from_points(vec![vec![0.0], vec![0.0, 1.0]]).is_none()
+        );
+    }
+}
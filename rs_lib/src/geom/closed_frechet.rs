@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::{frechet_dist, optimal_frechet_matching, segments};
+use crate::geom::Dist;
+
+#[derive(Serialize)]
+pub struct ClosedFrechet {
+    pub dist: Dist,
+    /// Lower bound on the optimal distance, which `dist` exceeds by at most a
+    /// small tolerance relative to the size of the curves
+    pub lower_bound: Dist,
+    /// Arc length on the second curve where the optimal matching starts
+    pub start_length: Dist,
+    /// Matching that realizes `dist`, as a polyline in the doubled parameter
+    /// domain `[0, L1] x [0, 2 * L2]`
+    pub matching: Vec<Point<Dist, 2>>,
+}
+
+/// Interval of start points `[lo, hi]` on the second curve, ordered such that
+/// the interval with the smallest lower bound on the distance comes first.
+struct Candidate {
+    lower_bound: Dist,
+    lo: (Dist, Dist),
+    hi: (Dist, Dist),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Dist::total_cmp(&other.lower_bound, &self.lower_bound)
+    }
+}
+
+impl Candidate {
+    fn new(lo: (Dist, Dist), hi: (Dist, Dist)) -> Self {
+        let ((lo_length, lo_dist), (hi_length, hi_dist)) = (lo, hi);
+        Self {
+            lower_bound: (lo_dist + hi_dist - (hi_length - lo_length)) / 2.,
+            lo,
+            hi,
+        }
+    }
+}
+
+/// Computes the Fréchet distance between the first curve and the closed second
+/// curve, minimized over all points at which the second curve can start. If
+/// the first curve is closed as well, this is the Fréchet distance between the
+/// two closed curves.
+///
+/// Moving the start point of the second curve by some arc length changes the
+/// distance by at most that length, so the distance is 1-Lipschitz in the
+/// start point. Starting from the vertices of the second curve and the points
+/// closest to the start of the first curve, intervals of start points are
+/// subdivided in order of their Lipschitz lower bound, until no interval can
+/// improve on the best distance by more than a small tolerance. Since the
+/// lower bound of an interval is within half its length of the distance at
+/// its ends, this takes finitely many steps, and the remaining gap is
+/// returned along with the distance.
+pub fn closed_frechet(curves: [&Curve; 2]) -> ClosedFrechet {
+    let [curve_1, curve_2] = curves;
+    assert!(curve_2.is_closed(), "second curve should be closed");
+
+    let total_length = curve_2.total_length();
+    let dist_at =
+        |length: Dist| frechet_dist([curve_1, &curve_2.with_start_at(length)]);
+
+    let start_1 = curve_1.points().first().unwrap();
+    let projections = segments(curve_2)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, segment)| {
+            curve_2.segment_param_to_length(idx, segment.closest_param(start_1))
+        })
+        .collect_vec();
+
+    let evaluated = curve_2
+        .cumulative_lengths()
+        .iter()
+        .copied()
+        .chain(projections)
+        .sorted_by(Dist::total_cmp)
+        .dedup()
+        .map(|length| (length, dist_at(length)))
+        .collect_vec();
+
+    let mut best = *evaluated
+        .iter()
+        .min_by(|(_, dist_1), (_, dist_2)| Dist::total_cmp(dist_1, dist_2))
+        .unwrap();
+
+    let tolerance = curve_1.total_length().max(total_length).max(best.1) * 1e-3;
+    let mut queue: BinaryHeap<Candidate> = evaluated
+        .into_iter()
+        .tuple_windows()
+        .map(|(lo, hi)| Candidate::new(lo, hi))
+        .collect();
+
+    let mut lower_bound = best.1;
+    while let Some(candidate) = queue.pop() {
+        if candidate.lower_bound >= best.1 - tolerance {
+            lower_bound = lower_bound.min(candidate.lower_bound);
+            break;
+        }
+
+        let Candidate { lo, hi, .. } = candidate;
+        let length = (lo.0 + hi.0) / 2.;
+        let mid = (length, dist_at(length));
+        if mid.1 < best.1 {
+            best = mid;
+        }
+
+        queue.push(Candidate::new(lo, mid));
+        queue.push(Candidate::new(mid, hi));
+    }
+
+    let (start_length, dist) = best;
+    let matching = optimal_frechet_matching([
+        curve_1,
+        &curve_2.with_start_at(start_length),
+    ])
+    .into_iter()
+    .map(|p| Point::from([p.x, p.y + start_length]))
+    .collect();
+
+    ClosedFrechet {
+        dist,
+        lower_bound: lower_bound.max(0.),
+        start_length,
+        matching,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn shifted_square() {
+        let square = vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+            point![0.0, 2.0],
+        ];
+        let curve_1 = Curve::closed_from_points(square.clone());
+        let curve_2 = Curve::closed_from_points(
+            square.into_iter().cycle().skip(2).take(4).collect(),
+        );
+
+        // Matched from their first vertices, the squares are far apart
+        assert!(frechet_dist([&curve_1, &curve_2]) > 2.0);
+
+        let result = closed_frechet([&curve_1, &curve_2]);
+        assert_relative_eq!(result.dist, 0.0, epsilon = 1e-3);
+        assert!(result.lower_bound <= result.dist);
+        assert_relative_eq!(result.start_length, 4.0, epsilon = 1e-2);
+        assert_relative_eq!(
+            *result.matching.last().unwrap(),
+            point![8.0, 12.0],
+            epsilon = 1e-2
+        );
+    }
+}
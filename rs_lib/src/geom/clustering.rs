@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::geom::curve::Curve;
+use crate::geom::discrete_frechet::discrete_frechet;
+use crate::geom::dtw::{dtw, DtwOptions};
+use crate::geom::frechet::frechet_dist;
+use crate::geom::hausdorff::hausdorff;
+use crate::geom::Dist;
+
+/// Distance measures between curves that can be used for clustering.
+#[derive(Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveDistance {
+    DiscreteFrechet,
+    Frechet,
+    Dtw,
+    Hausdorff,
+}
+
+impl CurveDistance {
+    pub fn eval(self, curves: [&Curve; 2]) -> Dist {
+        match self {
+            Self::DiscreteFrechet => discrete_frechet(curves).dist,
+            Self::Frechet => frechet_dist(curves),
+            Self::Dtw => {
+                dtw(curves, &DtwOptions::default())
+                    .expect("unconstrained DTW should admit a warping path")
+                    .cost
+            }
+            Self::Hausdorff => hausdorff(curves[0], curves[1]).dist,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Clustering {
+    /// Indices of the input curves that serve as cluster centers
+    pub centers: Vec<usize>,
+    /// Index into `centers` of the cluster of each input curve
+    pub assignments: Vec<usize>,
+    /// Largest (k-center) or total (k-medians) distance from the input curves
+    /// to their cluster centers
+    pub cost: Dist,
+}
+
+/// Distances between pairs of input curves, computed on first use since the
+/// curve distances are expensive and often only a fraction of them is needed.
+struct Distances<'c, F> {
+    curves: &'c [&'c Curve],
+    dist_fn: F,
+    /// Distance between curves `i < j`, stored at key `(i, j)`
+    cache: HashMap<(usize, usize), Dist>,
+}
+
+impl<'c, F: Fn([&Curve; 2]) -> Dist> Distances<'c, F> {
+    fn new(curves: &'c [&'c Curve], dist_fn: F) -> Self {
+        Self {
+            curves,
+            dist_fn,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, i: usize, j: usize) -> Dist {
+        if i == j {
+            return 0.;
+        }
+        let (i, j) = (i.min(j), i.max(j));
+        *self
+            .cache
+            .entry((i, j))
+            .or_insert_with(|| (self.dist_fn)([self.curves[i], self.curves[j]]))
+    }
+
+    /// Assigns each curve to its closest center, and returns the assignments
+    /// together with the distances to the assigned centers.
+    fn assign(&mut self, centers: &[usize]) -> (Vec<usize>, Vec<Dist>) {
+        (0..self.curves.len())
+            .map(|idx| {
+                centers
+                    .iter()
+                    .enumerate()
+                    .map(|(center_idx, &center)| {
+                        (center_idx, self.get(idx, center))
+                    })
+                    .min_by(|(_, dist_1), (_, dist_2)| {
+                        Dist::total_cmp(dist_1, dist_2)
+                    })
+                    .unwrap()
+            })
+            .unzip()
+    }
+}
+
+fn k_center_with<F: Fn([&Curve; 2]) -> Dist>(
+    distances: &mut Distances<F>,
+    k: usize,
+) -> Option<Clustering> {
+    let n = distances.curves.len();
+    if n == 0 || k == 0 {
+        return None;
+    }
+
+    let mut centers = vec![0];
+    let mut closest_dists =
+        (0..n).map(|idx| distances.get(idx, 0)).collect_vec();
+
+    while centers.len() < k.min(n) {
+        let (farthest, _) = closest_dists
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, dist_1), (_, dist_2)| Dist::total_cmp(dist_1, dist_2))
+            .unwrap();
+        centers.push(farthest);
+
+        for (idx, closest_dist) in closest_dists.iter_mut().enumerate() {
+            *closest_dist = closest_dist.min(distances.get(idx, farthest));
+        }
+    }
+
+    let (assignments, dists) = distances.assign(&centers);
+    Some(Clustering {
+        centers,
+        assignments,
+        cost: dists.into_iter().fold(0., Dist::max),
+    })
+}
+
+/// Clusters the curves around `k` of them such that the largest distance from
+/// a curve to its cluster center is small, using Gonzalez' farthest-first
+/// traversal. The result is a 2-approximation for metric distances. Returns
+/// `None` if `k` is 0 or no curves are given, since no curve can be assigned.
+pub fn k_center(
+    curves: &[&Curve],
+    k: usize,
+    dist_fn: impl Fn([&Curve; 2]) -> Dist,
+) -> Option<Clustering> {
+    k_center_with(&mut Distances::new(curves, dist_fn), k)
+}
+
+/// Clusters the curves around `k` of them such that the total distance from
+/// the curves to their cluster centers is small.
+///
+/// Starting from the k-center clustering, a local search swaps a center with
+/// another curve whenever that lowers the total distance, until no single
+/// swap improves it or `max_swaps` swaps have been made. Returns `None` if `k`
+/// is 0 or no curves are given.
+pub fn k_medians(
+    curves: &[&Curve],
+    k: usize,
+    dist_fn: impl Fn([&Curve; 2]) -> Dist,
+    max_swaps: usize,
+) -> Option<Clustering> {
+    let mut distances = Distances::new(curves, dist_fn);
+    let n = curves.len();
+
+    let mut centers = k_center_with(&mut distances, k)?.centers;
+    let total_cost =
+        |distances: &mut Distances<_>, centers: &[usize]| -> Dist {
+            distances.assign(centers).1.into_iter().sum()
+        };
+    let mut cost = total_cost(&mut distances, &centers);
+
+    for _ in 0..max_swaps {
+        let improving_swap =
+            Itertools::cartesian_product(0..centers.len(), 0..n)
+                .filter(|(_, candidate)| !centers.contains(candidate))
+                .find_map(|(center_idx, candidate)| {
+                    let mut new_centers = centers.clone();
+                    new_centers[center_idx] = candidate;
+                    let new_cost = total_cost(&mut distances, &new_centers);
+                    (new_cost < cost).then_some((new_centers, new_cost))
+                });
+
+        match improving_swap {
+            Some((new_centers, new_cost)) => {
+                (centers, cost) = (new_centers, new_cost)
+            }
+            None => break,
+        }
+    }
+
+    let (assignments, _) = distances.assign(&centers);
+    Some(Clustering {
+        centers,
+        assignments,
+        cost,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn two_bundles() {
+        let curves = [0.0, 0.1, 0.2, 5.0, 5.3]
+            .map(|y| Curve::from_points(vec![point![0.0, y], point![2.0, y]]));
+        let curves = curves.iter().collect_vec();
+
+        for distance in [
+            CurveDistance::DiscreteFrechet,
+            CurveDistance::Frechet,
+            CurveDistance::Hausdorff,
+        ] {
+            let dist_fn = |curves: [&Curve; 2]| distance.eval(curves);
+
+            let result = k_center(&curves, 2, dist_fn).unwrap();
+            assert_eq!(result.centers, vec![0, 4]);
+            assert_eq!(result.assignments, vec![0, 0, 0, 1, 1]);
+            assert_relative_eq!(result.cost, 0.3, epsilon = 1e-4);
+
+            let result = k_medians(&curves, 2, dist_fn, 10).unwrap();
+            assert_eq!(result.assignments, vec![0, 0, 0, 1, 1]);
+            assert!(result.centers.contains(&1));
+            assert_relative_eq!(result.cost, 0.5, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn no_clusters() {
+        let curve =
+            Curve::from_points(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+        let dist_fn = |curves: [&Curve; 2]| CurveDistance::Frechet.eval(curves);
+
+        assert!(k_center(&[&curve], 0, dist_fn).is_none());
+        assert!(k_medians(&[&curve], 0, dist_fn, 10).is_none());
+        assert!(k_center(&[], 2, dist_fn).is_none());
+    }
+}
@@ -1,19 +1,53 @@
+use std::cell::OnceCell;
+use std::iter;
+
 use itertools::Itertools;
-use nalgebra::Point;
+use nalgebra::{Point, SVector, Similarity2};
+use serde::{Deserialize, Serialize};
 
 use crate::geom::line_segment::LineSegment;
+use crate::geom::segment_bvh::SegmentBvh;
 use crate::geom::Dist;
 use crate::math::function::Function;
 use crate::Mix;
 
+/// Parameter values that a curve takes at its points, and between which it
+/// moves at constant speed. Regardless of the parameterization, every
+/// parameter starts at 0 at the first point.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Parameterization {
+    /// Arc length, i.e. the curve moves at unit speed
+    #[default]
+    ArcLength,
+    /// Each segment takes unit time, so the parameter at a point is its index
+    VertexIndex,
+    /// Arc length divided by the total length, ranging over `[0, 1]`
+    Normalized,
+    /// Time at which each point is visited, e.g. recorded with the positions
+    /// of a trajectory. There has to be one nondecreasing timestamp per point,
+    /// including the repeated first point of a closed curve.
+    Timestamps { timestamps: Vec<Dist> },
+}
+
+/// Polygonal curve in `D`-dimensional space, parameterized by arc length
+/// unless a different [`Parameterization`] is given.
 #[derive(Debug, Default, Clone)]
-pub struct Curve {
-    points: Vec<Point<Dist, 2>>,
+pub struct Curve<const D: usize = 2> {
+    points: Vec<Point<Dist, D>>,
     cumulative_lengths: Vec<Dist>,
+    parameterization: Parameterization,
+    /// Parameter values at the points, according to `parameterization`
+    cumulative_params: Vec<Dist>,
+    /// Whether the last point connects back to the first. The first point of a
+    /// closed curve is repeated at the end of `points`.
+    closed: bool,
+    /// Hierarchy over the segments, built on first use
+    segment_bvh: OnceCell<SegmentBvh<D>>,
 }
 
-impl Curve {
-    fn compute_cumulative_lengths(points: &Vec<Point<Dist, 2>>) -> Vec<Dist> {
+impl<const D: usize> Curve<D> {
+    fn compute_cumulative_lengths(points: &Vec<Point<Dist, D>>) -> Vec<Dist> {
         (0..points.len())
             .scan(0., |cumulative_length, idx| {
                 if idx != 0 {
@@ -25,14 +59,147 @@ impl Curve {
             .collect()
     }
 
-    pub fn from_points(points: Vec<Point<Dist, 2>>) -> Self {
+    /// Returns the parameter values at the points, or `None` if the
+    /// timestamps don't fit the points.
+    fn compute_cumulative_params(&self) -> Option<Vec<Dist>> {
+        let lengths = &self.cumulative_lengths;
+        match &self.parameterization {
+            Parameterization::ArcLength => Some(lengths.clone()),
+            Parameterization::VertexIndex => {
+                Some((0..lengths.len()).map(|idx| idx as Dist).collect())
+            }
+            Parameterization::Normalized => {
+                let total_length = lengths.last().copied().unwrap_or(0.);
+                Some(
+                    lengths
+                        .iter()
+                        .map(|length| {
+                            if total_length > 0. {
+                                length / total_length
+                            } else {
+                                0.
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            Parameterization::Timestamps { timestamps } => {
+                let valid = timestamps.len() == self.points.len()
+                    && timestamps
+                        .iter()
+                        .tuple_windows()
+                        .all(|(t1, t2)| t1 <= t2);
+                valid.then(|| {
+                    timestamps.iter().map(|t| t - timestamps[0]).collect()
+                })
+            }
+        }
+    }
+
+    /// Returns a copy of the curve parameterized according to
+    /// `parameterization`, or `None` if it has timestamps that are not
+    /// nondecreasing or whose number differs from the number of points.
+    pub fn with_parameterization(
+        &self,
+        parameterization: Parameterization,
+    ) -> Option<Self> {
+        let mut curve = Self {
+            parameterization,
+            ..self.clone()
+        };
+        curve.cumulative_params = curve.compute_cumulative_params()?;
+        Some(curve)
+    }
+
+    /// Returns this curve, derived from `other`, with the parameterization of
+    /// `other`, or by arc length if its timestamps no longer fit the points.
+    fn parameterized_like(self, other: &Self) -> Self {
+        let parameterization = other.parameterization.clone();
+        self.with_parameterization(parameterization).unwrap_or(self)
+    }
+
+    pub fn from_points(points: Vec<Point<Dist, D>>) -> Self {
+        let cumulative_lengths = Self::compute_cumulative_lengths(&points);
         Self {
-            cumulative_lengths: Self::compute_cumulative_lengths(&points),
+            cumulative_params: cumulative_lengths.clone(),
+            cumulative_lengths,
+            parameterization: Parameterization::ArcLength,
             points,
+            closed: false,
+            segment_bvh: OnceCell::new(),
+        }
+    }
+
+    /// Creates a closed curve through `points`, connecting the last point back
+    /// to the first.
+    pub fn closed_from_points(mut points: Vec<Point<Dist, D>>) -> Self {
+        if let Some(&first) = points.first() {
+            points.push(first);
+        }
+        Self {
+            closed: true,
+            ..Self::from_points(points)
+        }
+    }
+
+    /// Returns a copy of the curve through the same vertices that is closed or
+    /// open according to `closed`.
+    pub fn with_closed(&self, closed: bool) -> Self {
+        let vertices = self.vertices().to_vec();
+        if closed {
+            Self::closed_from_points(vertices)
+        } else {
+            Self::from_points(vertices)
         }
+        .parameterized_like(self)
     }
 
-    pub fn push(&mut self, point: Point<Dist, 2>) {
+    /// Returns a curve through `vertices` that is closed if this curve is.
+    pub fn with_vertices(&self, vertices: Vec<Point<Dist, D>>) -> Self {
+        if self.closed {
+            Self::closed_from_points(vertices)
+        } else {
+            Self::from_points(vertices)
+        }
+        .parameterized_like(self)
+    }
+
+    /// Appends `point` to the curve, before the repeated first point of a
+    /// closed curve. A curve parameterized by timestamps also needs the
+    /// `timestamp` at which the point is visited, while other curves ignore
+    /// it. The closing segment of a closed curve keeps its duration.
+    ///
+    /// Returns an error and leaves the curve unchanged if the timestamp is
+    /// missing or precedes the last one.
+    pub fn push(
+        &mut self,
+        point: Point<Dist, D>,
+        timestamp: Option<Dist>,
+    ) -> Result<(), String> {
+        if let Parameterization::Timestamps { timestamps } =
+            &self.parameterization
+        {
+            let Some(timestamp) = timestamp else {
+                return Err("a curve parameterized by timestamps needs a \
+                    timestamp for each new point"
+                    .into());
+            };
+            let last_vertex =
+                timestamps.len().saturating_sub(usize::from(self.closed));
+            if let Some(&last) = timestamps[..last_vertex].last() {
+                if timestamp < last {
+                    return Err(format!(
+                        "timestamp {timestamp} precedes the last one, {last}"
+                    ));
+                }
+            }
+        }
+
+        if self.closed {
+            self.push_closed(point, timestamp);
+            return Ok(());
+        }
+
         let new_length =
             match (self.points.last(), self.cumulative_lengths.last()) {
                 (Some(last_point), Some(last_length)) => {
@@ -41,16 +208,63 @@ impl Curve {
                 (None, None) => 0.,
                 _ => unreachable!(),
             };
-
         self.points.push(point);
         self.cumulative_lengths.push(new_length);
+        self.segment_bvh = OnceCell::new();
+
+        match &mut self.parameterization {
+            Parameterization::ArcLength => {
+                self.cumulative_params.push(new_length)
+            }
+            Parameterization::VertexIndex => {
+                self.cumulative_params.push((self.points.len() - 1) as Dist)
+            }
+            // Every parameter is relative to the new total length
+            Parameterization::Normalized => {
+                self.cumulative_params =
+                    self.compute_cumulative_params().unwrap()
+            }
+            Parameterization::Timestamps { timestamps } => {
+                let timestamp = timestamp.unwrap();
+                timestamps.push(timestamp);
+                self.cumulative_params.push(timestamp - timestamps[0]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts `point` before the repeated first point of this closed curve,
+    /// whose closing timestamp moves by the same amount as the last one.
+    fn push_closed(&mut self, point: Point<Dist, D>, timestamp: Option<Dist>) {
+        let parameterization =
+            if let Parameterization::Timestamps { timestamps } =
+                &self.parameterization
+            {
+                let mut timestamps = timestamps.clone();
+                let closing_duration =
+                    match (timestamps.pop(), timestamps.last()) {
+                        (Some(closing), Some(last)) => closing - last,
+                        _ => 0.,
+                    };
+                let timestamp = timestamp.unwrap();
+                timestamps.extend([timestamp, timestamp + closing_duration]);
+                Parameterization::Timestamps { timestamps }
+            } else {
+                self.parameterization.clone()
+            };
+
+        let mut vertices = self.vertices().to_vec();
+        vertices.push(point);
+        *self = Self::closed_from_points(vertices)
+            .with_parameterization(parameterization)
+            .unwrap();
     }
 
     pub fn total_length(&self) -> Dist {
         *self.cumulative_lengths.last().unwrap()
     }
 
-    pub fn points(&self) -> &Vec<Point<Dist, 2>> {
+    pub fn points(&self) -> &Vec<Point<Dist, D>> {
         &self.points
     }
 
@@ -58,9 +272,221 @@ impl Curve {
         &self.cumulative_lengths
     }
 
+    pub fn parameterization(&self) -> &Parameterization {
+        &self.parameterization
+    }
+
+    /// Returns the parameter values at the points.
+    pub fn cumulative_params(&self) -> &Vec<Dist> {
+        &self.cumulative_params
+    }
+
+    /// Returns the parameter value at the end of the curve.
+    pub fn total_param(&self) -> Dist {
+        *self.cumulative_params.last().unwrap()
+    }
+
+    /// Returns the arc length at parameter value `param`. Closed curves wrap
+    /// around, while open curves are clamped to their endpoints.
+    pub fn param_to_length(&self, param: Dist) -> Dist {
+        let param = if self.closed && self.total_param() > 0. {
+            param.rem_euclid(self.total_param())
+        } else {
+            param.clamp(0., self.total_param())
+        };
+        interpolate(&self.cumulative_params, &self.cumulative_lengths, param)
+    }
+
+    /// Returns the parameter value at arc length `length`, which is the first
+    /// one at which the curve gets there if it stays at a point for a while.
+    /// Lengths beyond the ends of the curve are clamped.
+    pub fn length_to_param(&self, length: Dist) -> Dist {
+        let length = length.clamp(0., self.total_length());
+        interpolate(&self.cumulative_lengths, &self.cumulative_params, length)
+    }
+
+    /// Returns the point at parameter value `param`.
+    pub fn point_at_param(&self, param: Dist) -> Point<Dist, D> {
+        self.eval(self.param_to_length(param))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the points defining the curve, i.e. [`Curve::points`] without
+    /// the repeated first point of a closed curve.
+    pub fn vertices(&self) -> &[Point<Dist, D>] {
+        if self.closed {
+            &self.points[..self.points.len().saturating_sub(1)]
+        } else {
+            &self.points
+        }
+    }
+
+    /// Returns a copy of the closed curve that starts and ends at arc length
+    /// `length`, traversing the same loop. Timestamps are rotated along, such
+    /// that the part before the new start is visited one period later.
+    pub fn with_start_at(&self, length: Dist) -> Self {
+        assert!(self.closed, "only closed curves can be shifted cyclically");
+        if self.points.is_empty() {
+            return self.clone();
+        }
+
+        let start = self.eval(length);
+        let length = length.rem_euclid(self.total_length().max(Dist::EPSILON));
+
+        let after = self
+            .cumulative_lengths
+            .partition_point(|&cumulative_length| cumulative_length <= length);
+        let before = self
+            .cumulative_lengths
+            .partition_point(|&cumulative_length| cumulative_length < length);
+
+        let points = iter::once(start)
+            .chain(self.points[after..].iter().copied())
+            .chain(self.points[1..before.max(1)].iter().copied())
+            .chain(iter::once(start))
+            .collect();
+        let shifted = Self {
+            closed: true,
+            ..Self::from_points(points)
+        };
+
+        match &self.parameterization {
+            Parameterization::Timestamps { timestamps } => {
+                let period = self.total_param();
+                let start_time = timestamps[0] + self.length_to_param(length);
+                let timestamps = iter::once(start_time)
+                    .chain(timestamps[after..].iter().copied())
+                    .chain(
+                        timestamps[1..before.max(1)].iter().map(|t| t + period),
+                    )
+                    .chain(iter::once(start_time + period))
+                    // Keep rounding errors from reordering equal timestamps
+                    .scan(Dist::NEG_INFINITY, |latest, t| {
+                        *latest = latest.max(t);
+                        Some(*latest)
+                    })
+                    .collect();
+                shifted
+                    .with_parameterization(Parameterization::Timestamps {
+                        timestamps,
+                    })
+                    .expect("rotated timestamps should fit the points")
+            }
+            _ => shifted.parameterized_like(self),
+        }
+    }
+
+    /// Returns the open curve that traverses the closed curve twice, which
+    /// spans the doubled parameter domain in which all cyclic shifts of the
+    /// curve can be matched.
+    pub fn doubled(&self) -> Self {
+        let doubled = Self::from_points(
+            self.points
+                .iter()
+                .chain(self.points.iter().skip(1))
+                .copied()
+                .collect(),
+        );
+
+        // The second loop is visited one period after the first
+        match &self.parameterization {
+            Parameterization::Timestamps { timestamps } => {
+                let period = self.total_param();
+                let timestamps = timestamps
+                    .iter()
+                    .copied()
+                    .chain(timestamps.iter().skip(1).map(|t| t + period))
+                    .collect();
+                doubled
+                    .with_parameterization(Parameterization::Timestamps {
+                        timestamps,
+                    })
+                    .expect("doubled timestamps should fit the points")
+            }
+            _ => doubled.parameterized_like(self),
+        }
+    }
+
+    /// Returns the arc length of the point at parameter `t` in `[0, 1]` along
+    /// the segment with index `segment_idx`.
+    pub fn segment_param_to_length(&self, segment_idx: usize, t: Dist) -> Dist {
+        let last_idx = self.cumulative_lengths.len() - 1;
+        let length_1 = self.cumulative_lengths[segment_idx.min(last_idx)];
+        let length_2 = self.cumulative_lengths[(segment_idx + 1).min(last_idx)];
+        length_1.mix(length_2, t)
+    }
+
+    /// Returns the parameter value of the point at parameter `t` in `[0, 1]`
+    /// along the segment with index `segment_idx`.
+    pub fn segment_param_to_param(&self, segment_idx: usize, t: Dist) -> Dist {
+        let last_idx = self.cumulative_params.len() - 1;
+        let param_1 = self.cumulative_params[segment_idx.min(last_idx)];
+        let param_2 = self.cumulative_params[(segment_idx + 1).min(last_idx)];
+        param_1.mix(param_2, t)
+    }
+
+    /// Returns the velocity of the curve at parameter value `param`, i.e. its
+    /// derivative, which is the unit direction of the curve when it is
+    /// parameterized by arc length. At a vertex this is the velocity along the
+    /// outgoing segment, except at the end of an open curve, where it is the
+    /// velocity along the last segment. Beyond the ends of an open curve,
+    /// where it is clamped to its endpoints, the derivative is zero.
+    pub fn derivative_at(&self, param: Dist) -> SVector<Dist, D> {
+        let params = &self.cumulative_params;
+        let total_param = self.total_param();
+        let param = if self.closed && total_param > 0. {
+            param.rem_euclid(total_param)
+        } else if (0. ..=total_param).contains(&param) {
+            param
+        } else {
+            return SVector::zeros();
+        };
+
+        let idx = params
+            .partition_point(|&cumulative_param| cumulative_param <= param)
+            .min(params.len() - 1);
+
+        // Skip segments that take no time at the end of the curve
+        (1..=idx)
+            .rev()
+            .find(|&idx| params[idx - 1] < params[idx])
+            .map_or_else(SVector::zeros, |idx| {
+                (self.points[idx] - self.points[idx - 1])
+                    / (params[idx] - params[idx - 1])
+            })
+    }
+
+    /// Returns a copy of the curve with all points shifted by `translation`.
+    pub fn translated(&self, translation: &SVector<Dist, D>) -> Self {
+        Self {
+            points: self.points.iter().map(|p| p + translation).collect(),
+            cumulative_lengths: self.cumulative_lengths.clone(),
+            parameterization: self.parameterization.clone(),
+            cumulative_params: self.cumulative_params.clone(),
+            closed: self.closed,
+            segment_bvh: OnceCell::new(),
+        }
+    }
+
+    /// Returns the bounding-volume hierarchy over the segments of the curve.
+    pub fn segment_bvh(&self) -> &SegmentBvh<D> {
+        self.segment_bvh.get_or_init(|| {
+            SegmentBvh::new(
+                self.points
+                    .iter()
+                    .tuple_windows()
+                    .map(|(p, q)| [*p, *q])
+                    .collect(),
+            )
+        })
+    }
+
     pub fn line_segments(
         &self,
-    ) -> impl Iterator<Item = LineSegment<Dist, 2>> + Clone + '_ {
+    ) -> impl Iterator<Item = LineSegment<Dist, D>> + Clone + '_ {
         self.points
             .iter()
             .cloned()
@@ -69,11 +495,76 @@ impl Curve {
     }
 }
 
-impl<'f> Function<'f, Dist> for Curve {
-    type Output = Point<Dist, 2>;
+/// Returns the value of the piecewise linear function through the points
+/// `(xs[i], ys[i])` at `x`, where `xs` is nondecreasing. Where `xs` repeats
+/// `x`, the first corresponding value in `ys` is returned.
+fn interpolate(xs: &[Dist], ys: &[Dist], x: Dist) -> Dist {
+    let idx = xs.partition_point(|&value| value < x).min(xs.len() - 1);
+    if idx == 0 {
+        return ys[0];
+    }
+
+    let t = (x - xs[idx - 1]) / (xs[idx] - xs[idx - 1]);
+    ys[idx - 1].mix(ys[idx], t)
+}
+
+/// Subdivides the nondecreasing `lengths` so that consecutive values are at
+/// most `res` apart, and returns the part covering `[min, max]`.
+pub fn subdivide_lengths(
+    lengths: &[Dist],
+    res: Dist,
+    [min, max]: [Dist; 2],
+) -> Vec<Dist> {
+    if lengths.is_empty() {
+        return vec![];
+    }
+
+    let lengths =
+        iter::once(*lengths.first().unwrap())
+            .chain(lengths.iter().tuple_windows::<(_, _)>().flat_map(
+                |(l1, l2)| {
+                    // Note: since `num_subdivisions` is 0 if both lengths are equal, this
+                    // effectively also deduplicates the lengths
+                    let num_subdivisions = ((l2 - l1) / res).ceil() as usize;
+                    (0..num_subdivisions).map(move |i| {
+                        let t = (i + 1) as Dist / (num_subdivisions as Dist);
+                        l1 * (1. - t) + l2 * t
+                    })
+                },
+            ))
+            .collect_vec();
+
+    let lo = lengths.partition_point(|&length| length <= min).max(1) - 1;
+    let hi = lengths
+        .partition_point(|&length| length <= max)
+        .min(lengths.len() - 1);
+    lengths[lo..=hi].to_vec()
+}
+
+impl Curve<2> {
+    /// Returns a copy of the curve with `transform` applied to all points.
+    pub fn transformed(&self, transform: &Similarity2<Dist>) -> Self {
+        Self {
+            closed: self.closed,
+            ..Self::from_points(
+                self.points.iter().map(|p| transform * p).collect(),
+            )
+        }
+        .parameterized_like(self)
+    }
+}
+
+impl<'f, const D: usize> Function<'f, Dist> for Curve<D> {
+    type Output = Point<Dist, D>;
 
     fn eval(&'f self, length: Dist) -> Self::Output {
-        let length = length.clamp(0., self.total_length());
+        // Closed curves wrap around, so that they can be evaluated on the
+        // doubled parameter domain
+        let length = if self.closed && self.total_length() > 0. {
+            length.rem_euclid(self.total_length())
+        } else {
+            length.clamp(0., self.total_length())
+        };
 
         let idx = self
             .cumulative_lengths
@@ -99,7 +590,7 @@ impl<'f> Function<'f, Dist> for Curve {
 #[cfg(test)]
 mod test {
     use approx::assert_relative_eq;
-    use nalgebra::point;
+    use nalgebra::{point, vector};
 
     use super::*;
 
@@ -120,4 +611,205 @@ mod test {
         assert_relative_eq!(curve.eval(1.8), point![1.8, 0.0]);
         assert_relative_eq!(curve.eval(2.0), point![2.0, 0.0]);
     }
+
+    #[test]
+    fn closed_curve() {
+        let curve = Curve::closed_from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ]);
+
+        assert_eq!(curve.vertices().len(), 4);
+        assert_relative_eq!(curve.total_length(), 4.0);
+        assert_relative_eq!(curve.eval(4.5), point![0.5, 0.0]);
+
+        let shifted = curve.with_start_at(1.5);
+        assert!(shifted.is_closed());
+        assert_relative_eq!(shifted.total_length(), 4.0);
+        assert_relative_eq!(shifted.eval(0.0), point![1.0, 0.5]);
+        assert_relative_eq!(shifted.eval(0.5), point![1.0, 1.0]);
+        assert_relative_eq!(shifted.eval(3.0), point![0.5, 0.0]);
+
+        let doubled = curve.doubled();
+        assert!(!doubled.is_closed());
+        assert_relative_eq!(doubled.total_length(), 8.0);
+        assert_relative_eq!(doubled.eval(5.5), curve.eval(1.5));
+    }
+
+    #[test]
+    fn parameterizations() {
+        let curve = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 1.0],
+        ]);
+
+        let by_index = curve
+            .with_parameterization(Parameterization::VertexIndex)
+            .unwrap();
+        assert_eq!(by_index.cumulative_params(), &vec![0.0, 1.0, 2.0]);
+        assert_relative_eq!(by_index.point_at_param(0.5), point![1.0, 0.0]);
+        assert_relative_eq!(by_index.point_at_param(1.5), point![2.0, 0.5]);
+        assert_relative_eq!(by_index.derivative_at(0.5), vector![2.0, 0.0]);
+        assert_relative_eq!(by_index.length_to_param(2.5), 1.5);
+
+        let normalized = curve
+            .with_parameterization(Parameterization::Normalized)
+            .unwrap();
+        assert_relative_eq!(normalized.total_param(), 1.0);
+        assert_relative_eq!(normalized.param_to_length(0.5), 1.5);
+
+        // Timestamps are relative to the first one
+        let timed = curve
+            .with_parameterization(Parameterization::Timestamps {
+                timestamps: vec![10.0, 11.0, 15.0],
+            })
+            .unwrap();
+        assert_relative_eq!(timed.total_param(), 5.0);
+        assert_relative_eq!(timed.point_at_param(3.0), point![2.0, 0.5]);
+        assert_relative_eq!(timed.derivative_at(3.0), vector![0.0, 0.25]);
+
+        // Timestamps have to fit the points
+        for timestamps in [vec![0.0, 1.0], vec![0.0, 2.0, 1.0]] {
+            assert!(curve
+                .with_parameterization(Parameterization::Timestamps {
+                    timestamps
+                })
+                .is_none());
+        }
+
+        // New points keep the parameterization
+        let mut extended = by_index.clone();
+        extended.push(point![0.0, 1.0], None).unwrap();
+        assert_relative_eq!(extended.total_param(), 3.0);
+        let mut extended = normalized.clone();
+        extended.push(point![0.0, 1.0], None).unwrap();
+        assert_relative_eq!(extended.param_to_length(0.5), 2.5);
+    }
+
+    #[test]
+    fn push_timestamps() {
+        let mut curve =
+            Curve::from_points(vec![point![0.0, 0.0], point![1.0, 0.0]])
+                .with_parameterization(Parameterization::Timestamps {
+                    timestamps: vec![10.0, 11.0],
+                })
+                .unwrap();
+
+        curve.push(point![1.0, 1.0], Some(14.0)).unwrap();
+        assert_eq!(
+            curve.parameterization(),
+            &Parameterization::Timestamps {
+                timestamps: vec![10.0, 11.0, 14.0]
+            }
+        );
+        assert_relative_eq!(curve.point_at_param(2.5), point![1.0, 0.5]);
+
+        // Missing or earlier timestamps are rejected
+        assert!(curve.push(point![0.0, 1.0], None).is_err());
+        assert!(curve.push(point![0.0, 1.0], Some(13.0)).is_err());
+        assert_eq!(curve.points().len(), 3);
+
+        // The closing segment of a closed curve keeps its duration
+        let mut closed = curve
+            .with_closed(true)
+            .with_parameterization(Parameterization::Timestamps {
+                timestamps: vec![10.0, 11.0, 14.0, 16.0],
+            })
+            .unwrap();
+        closed.push(point![0.0, 1.0], Some(15.0)).unwrap();
+        assert_eq!(
+            closed.parameterization(),
+            &Parameterization::Timestamps {
+                timestamps: vec![10.0, 11.0, 14.0, 15.0, 17.0]
+            }
+        );
+    }
+
+    #[test]
+    fn shifted_timestamps() {
+        let square = Curve::closed_from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ])
+        .with_parameterization(Parameterization::Timestamps {
+            timestamps: vec![0.0, 1.0, 3.0, 4.0, 6.0],
+        })
+        .unwrap();
+
+        // Halfway along the second edge, which takes from time 1 to 3
+        let shifted = square.with_start_at(1.5);
+        assert_eq!(
+            shifted.parameterization(),
+            &Parameterization::Timestamps {
+                timestamps: vec![2.0, 3.0, 4.0, 6.0, 7.0, 8.0]
+            }
+        );
+        assert_relative_eq!(shifted.total_param(), 6.0);
+        for param in [0.5, 2.5, 4.5] {
+            assert_relative_eq!(
+                shifted.point_at_param(param),
+                square.point_at_param(param + 2.0)
+            );
+        }
+    }
+
+    #[test]
+    fn waiting_curve() {
+        // Waits at the origin for two time units
+        let curve = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+        ])
+        .with_parameterization(Parameterization::Timestamps {
+            timestamps: vec![0.0, 2.0, 3.0],
+        })
+        .unwrap();
+
+        assert_relative_eq!(curve.point_at_param(1.0), point![0.0, 0.0]);
+        assert_relative_eq!(curve.point_at_param(2.5), point![0.5, 0.0]);
+        assert_relative_eq!(curve.length_to_param(0.0), 0.0);
+        assert_relative_eq!(curve.length_to_param(0.5), 2.5);
+        assert_relative_eq!(curve.derivative_at(1.0), vector![0.0, 0.0]);
+
+        // The second loop of a closed curve is one period later
+        let square = Curve::closed_from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ])
+        .with_parameterization(Parameterization::Timestamps {
+            timestamps: vec![0.0, 1.0, 3.0, 4.0, 6.0],
+        })
+        .unwrap();
+        let doubled = square.doubled();
+        assert_relative_eq!(doubled.total_param(), 12.0);
+        assert_relative_eq!(
+            doubled.point_at_param(8.0),
+            square.point_at_param(2.0)
+        );
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn projected_coordinates() {
+        // Many short steps far from the origin, as in UTM coordinates
+        let points = (0..10_000)
+            .map(|idx| point![500_000.0 + idx as Dist * 0.1, 5_000_000.0])
+            .collect();
+        let curve = Curve::from_points(points);
+
+        assert_relative_eq!(curve.total_length(), 999.9, epsilon = 1e-6);
+        assert_relative_eq!(
+            curve.eval(123.45),
+            point![500_123.45, 5_000_000.0],
+            epsilon = 1e-6
+        );
+    }
 }
@@ -1,74 +1,159 @@
-use itertools::Itertools;
 use nalgebra::Point;
 
 use crate::geom::curve::Curve;
+use crate::geom::metric::{Metric, PointMetric};
+use crate::geom::polygonal_domain::PolygonalDomain;
 use crate::geom::Dist;
 use crate::math::function::Function;
-use crate::math::partial_derivative::{
-    CentralDifferencePartialDerivativePlan, PartialDerivative,
-};
+use crate::math::partial_derivative::PartialDerivative;
 use crate::math::scalar_field::ScalarField;
 
-pub struct CurveDistFn<'f> {
-    curves: [&'f Curve; 2],
+/// Leash length between two curves in `D` dimensions, as a function on their
+/// 2D parameter space, in the parameterizations of the curves.
+pub struct CurveDistFn<'f, const D: usize = 2> {
+    curves: [&'f Curve<D>; 2],
+    /// Metric in which leash lengths are measured
+    metric: &'f dyn Metric<D>,
 }
 
-impl<'f> CurveDistFn<'f> {
-    pub fn new(curves: [&'f Curve; 2]) -> Self {
-        Self { curves }
+impl<'f, const D: usize> CurveDistFn<'f, D> {
+    pub fn new(curves: [&'f Curve<D>; 2]) -> Self {
+        Self {
+            curves,
+            metric: &PointMetric::Euclidean,
+        }
     }
 
-    pub fn max_dist_squared(&self) -> Dist {
-        Itertools::cartesian_product(
-            self.curves[0].points().iter(),
-            self.curves[1].points().iter(),
-        )
-        .map(|(p1, p2)| (p1 - p2).norm_squared())
-        .fold(Dist::NEG_INFINITY, |max_dist, dist| max_dist.max(dist))
+    /// Measures leash lengths in `metric` instead of the Euclidean metric.
+    pub fn with_metric(self, metric: &'f dyn Metric<D>) -> Self {
+        Self { metric, ..self }
     }
 
+    /// Returns the largest leash length.
     pub fn max_dist(&self) -> Dist {
-        self.max_dist_squared().sqrt()
+        let [points_1, points_2] = self.curves.map(|curve| curve.points());
+        self.metric.max_dist(points_1, points_2)
     }
 
-    pub fn min_dist_squared(&self) -> Dist {
-        Itertools::cartesian_product(
-            self.curves[0].line_segments(),
-            self.curves[1].line_segments(),
-        )
-        .map(|(l1, l2)| l1.dist_squared(&l2))
-        .fold(Dist::INFINITY, |min_dist, dist| min_dist.min(dist))
+    /// Returns the smallest distance between the curves, found by searching
+    /// their segment hierarchies. Metrics that are not convex along segments,
+    /// such as geodesic distances, only give a lower bound.
+    pub fn min_dist(&self) -> Dist {
+        let [bvh_1, bvh_2] = self.curves.map(|curve| curve.segment_bvh());
+        bvh_1.min_dist(bvh_2, self.metric)
     }
+}
 
-    pub fn min_dist(&self) -> Dist {
-        self.min_dist_squared().sqrt()
+impl<'f> CurveDistFn<'f, 2> {
+    /// Creates a distance function whose leash length is the geodesic distance
+    /// in `domain`, i.e. the length of a shortest path around its obstacles.
+    pub fn geodesic(
+        curves: [&'f Curve; 2],
+        domain: &'f PolygonalDomain,
+    ) -> Self {
+        Self::new(curves).with_metric(domain)
     }
 }
 
-impl<'f> Function<'f, Point<Dist, 2>> for CurveDistFn<'f> {
+impl<'f, const D: usize> Function<'f, Point<Dist, 2>> for CurveDistFn<'f, D> {
     type Output = Dist;
 
     fn eval(&self, p: Point<Dist, 2>) -> Self::Output {
         let [c1, c2] = self.curves;
-        let p1 = c1.eval(p.x);
-        let p2 = c2.eval(p.y);
-        (p1 - p2).norm()
+        let p1 = c1.point_at_param(p.x);
+        let p2 = c2.point_at_param(p.y);
+        self.metric.dist(&p1, &p2)
     }
 }
 
-impl<'f> ScalarField<'f, Dist, 2> for CurveDistFn<'f> {}
+impl<'f, const D: usize> ScalarField<'f, Dist, 2> for CurveDistFn<'f, D> {}
 
-impl<'f> PartialDerivative<'f, Dist, 2> for CurveDistFn<'f> {
-    type Output = CentralDifferencePartialDerivativePlan<'f, Dist, 2, Self>;
+impl<'f, const D: usize> PartialDerivative<'f, Dist, 2> for CurveDistFn<'f, D> {
+    type Output = CurveDistPartialDerivativePlan<'f, D>;
 
     fn partial_derivative(
         &'f self,
         var_idx: usize,
     ) -> <Self as PartialDerivative<'f, Dist, 2>>::Output {
-        CentralDifferencePartialDerivativePlan {
+        CurveDistPartialDerivativePlan {
             function: self,
-            spacing: 0.01,
             var_idx,
         }
     }
 }
+
+/// Exact partial derivative of a [`CurveDistFn`].
+///
+/// Moving along one of the curves changes the leash length by the derivative
+/// of the distance in the direction of its velocity, which is its unit tangent
+/// when it is parameterized by arc length. On the boundaries between cells,
+/// i.e. at vertices of the curve, the derivative is taken along the outgoing
+/// segment.
+pub struct CurveDistPartialDerivativePlan<'f, const D: usize = 2> {
+    function: &'f CurveDistFn<'f, D>,
+    var_idx: usize,
+}
+
+impl<'f, const D: usize> Function<'f, Point<Dist, 2>>
+    for CurveDistPartialDerivativePlan<'f, D>
+{
+    type Output = Dist;
+
+    fn eval(&self, p: Point<Dist, 2>) -> Self::Output {
+        let curves = self.function.curves;
+        let points =
+            [curves[0].point_at_param(p.x), curves[1].point_at_param(p.y)];
+
+        let [idx, other_idx] = [self.var_idx, 1 - self.var_idx];
+        let velocity = curves[idx].derivative_at(p[idx]);
+        self.function
+            .metric
+            .dist_gradient(&points[idx], &points[other_idx])
+            .dot(&velocity)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, Vector2};
+
+    use super::*;
+    use crate::math::gradient::Gradient;
+
+    #[test]
+    fn exact_gradient() {
+        let c1 = Curve::from_points(vec![point![0.0, 0.0], point![4.0, 0.0]]);
+        let c2 = Curve::from_points(vec![
+            point![0.0, 3.0],
+            point![3.0, 3.0],
+            point![3.0, 6.0],
+        ]);
+        let curve_dist_fn = CurveDistFn::new([&c1, &c2]);
+        let gradient_fn = curve_dist_fn.gradient();
+
+        // Leash from (1, 0) to (1, 3)
+        assert_relative_eq!(
+            gradient_fn.eval(point![1.0, 1.0]),
+            Vector2::new(0.0, 0.0)
+        );
+        // Leash from (1, 0) to (2, 3)
+        let expected = Vector2::new(-1.0, 1.0) / Dist::sqrt(10.);
+        assert_relative_eq!(gradient_fn.eval(point![1.0, 2.0]), expected);
+
+        // At the vertex of the second curve, the outgoing segment is used
+        let [_, dy] = gradient_fn.eval(point![3.0, 3.0]).into();
+        assert_relative_eq!(dy, 1.0);
+        // At the end of the first curve, its last segment is used, with the
+        // leash from (4, 0) to (0, 3)
+        let [dx, _] = gradient_fn.eval(point![4.0, 0.0]).into();
+        assert_relative_eq!(dx, 0.8);
+
+        let metric = PointMetric::Manhattan;
+        let curve_dist_fn = CurveDistFn::new([&c1, &c2]).with_metric(&metric);
+        assert_relative_eq!(
+            curve_dist_fn.gradient().eval(point![1.0, 2.0]),
+            Vector2::new(-1.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::discrete_frechet::discrete_frechet;
+use crate::geom::frechet::{frechet_decide, frechet_dist, tolerance};
+use crate::geom::Dist;
+
+/// Counts of how the stored curves were handled during a query, from cheapest
+/// to most expensive stage.
+#[derive(Default, Serialize)]
+pub struct SearchStats {
+    pub num_curves: usize,
+    /// Pruned because the distance between start or end points exceeds the
+    /// best upper bound
+    pub pruned_by_endpoints: usize,
+    /// Pruned because the distance between bounding box sides exceeds the
+    /// best upper bound
+    pub pruned_by_bounding_box: usize,
+    /// Curves whose discrete Fréchet distance was computed as upper bound
+    pub num_upper_bounds: usize,
+    /// Pruned because the decision procedure rejected the best upper bound
+    pub pruned_by_decision: usize,
+    /// Curves whose exact Fréchet distance was computed
+    pub num_exact: usize,
+}
+
+#[derive(Serialize)]
+pub struct NearestNeighbor {
+    /// Index of the closest stored curve
+    pub idx: usize,
+    /// Fréchet distance between the query and the closest stored curve
+    pub dist: Dist,
+    pub stats: SearchStats,
+}
+
+/// Axis-aligned bounding box, as its minimum and maximum corners.
+type BoundingBox = [Point<Dist, 2>; 2];
+
+fn bounding_box(curve: &Curve) -> BoundingBox {
+    let points = curve.points();
+    points
+        .iter()
+        .fold([points[0], points[0]], |[min, max], point| {
+            [min.inf(point), max.sup(point)]
+        })
+}
+
+/// Lower bound on the Fréchet distance from the distances between the start
+/// points and between the end points, which have to be matched to each other.
+fn endpoints_lower_bound(curves: [&Curve; 2]) -> Dist {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+    Dist::max(
+        (points_1.first().unwrap() - points_2.first().unwrap()).norm(),
+        (points_1.last().unwrap() - points_2.last().unwrap()).norm(),
+    )
+}
+
+/// Lower bound on the Fréchet distance from the bounding boxes of the curves:
+/// each extreme point of one curve is matched to a point of the other, so
+/// corresponding sides of the boxes lie at most the distance apart.
+fn bounding_box_lower_bound(boxes: [&BoundingBox; 2]) -> Dist {
+    let [[min_1, max_1], [min_2, max_2]] = boxes;
+    (min_1 - min_2).amax().max((max_1 - max_2).amax())
+}
+
+/// Index over a collection of curves that answers nearest-neighbor queries
+/// under the Fréchet distance.
+///
+/// Queries run the stored curves through a cascade of increasingly expensive
+/// filters: cheap lower bounds from endpoints and bounding boxes, discrete
+/// Fréchet distances as upper bounds, and the Fréchet decision procedure.
+/// Only curves that survive all of them get their exact distance computed.
+#[derive(Default)]
+pub struct CurveIndex {
+    curves: Vec<Curve>,
+    bounding_boxes: Vec<BoundingBox>,
+}
+
+impl CurveIndex {
+    pub fn new(curves: Vec<Curve>) -> Self {
+        let mut index = Self::default();
+        for curve in curves {
+            index.push(curve);
+        }
+        index
+    }
+
+    /// Adds a non-empty curve to the index.
+    pub fn push(&mut self, curve: Curve) {
+        assert!(!curve.points().is_empty(), "curve should not be empty");
+        self.bounding_boxes.push(bounding_box(&curve));
+        self.curves.push(curve);
+    }
+
+    /// Returns the stored curve with the smallest Fréchet distance to `query`,
+    /// or `None` if the index is empty.
+    pub fn nearest(&self, query: &Curve) -> Option<NearestNeighbor> {
+        let query_box = bounding_box(query);
+        let mut stats = SearchStats {
+            num_curves: self.curves.len(),
+            ..Default::default()
+        };
+
+        // Visit curves in order of their lower bounds, so that good upper
+        // bounds are found early
+        let lower_bounds = self
+            .curves
+            .iter()
+            .zip(&self.bounding_boxes)
+            .map(|(curve, curve_box)| {
+                [
+                    endpoints_lower_bound([query, curve]),
+                    bounding_box_lower_bound([&query_box, curve_box]),
+                ]
+            })
+            .collect_vec();
+        let order = (0..self.curves.len()).sorted_by(|&idx_1, &idx_2| {
+            let [bound_1, bound_2] = [idx_1, idx_2]
+                .map(|idx| lower_bounds[idx][0].max(lower_bounds[idx][1]));
+            Dist::total_cmp(&bound_1, &bound_2)
+        });
+
+        let mut upper_bound = Dist::INFINITY;
+        let mut nearest: Option<(usize, Dist)> = None;
+
+        for idx in order {
+            let curve = &self.curves[idx];
+            let [endpoints_bound, bounding_box_bound] = lower_bounds[idx];
+
+            if endpoints_bound > upper_bound {
+                stats.pruned_by_endpoints += 1;
+                continue;
+            }
+            if bounding_box_bound > upper_bound {
+                stats.pruned_by_bounding_box += 1;
+                continue;
+            }
+
+            stats.num_upper_bounds += 1;
+            upper_bound =
+                upper_bound.min(discrete_frechet([query, curve]).dist);
+
+            if !frechet_decide([query, curve], upper_bound * (1. + tolerance()))
+            {
+                stats.pruned_by_decision += 1;
+                continue;
+            }
+
+            stats.num_exact += 1;
+            let dist = frechet_dist([query, curve]);
+            upper_bound = upper_bound.min(dist);
+            if nearest.is_none_or(|(_, nearest_dist)| dist < nearest_dist) {
+                nearest = Some((idx, dist));
+            }
+        }
+
+        nearest.map(|(idx, dist)| NearestNeighbor { idx, dist, stats })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn nearest_line() {
+        let index = CurveIndex::new(
+            [0.0, 1.0, 2.0, 5.0, 10.0]
+                .map(|y| {
+                    Curve::from_points(vec![point![0.0, y], point![4.0, y]])
+                })
+                .into(),
+        );
+        let query = Curve::from_points(vec![
+            point![0.0, 1.8],
+            point![2.0, 2.5],
+            point![4.0, 1.8],
+        ]);
+
+        let result = index.nearest(&query).unwrap();
+        assert_eq!(result.idx, 2);
+        assert_relative_eq!(result.dist, 0.5, max_relative = 1e-4);
+
+        let stats = result.stats;
+        assert_eq!(stats.num_curves, 5);
+        assert_eq!(
+            stats.pruned_by_endpoints
+                + stats.pruned_by_bounding_box
+                + stats.pruned_by_decision
+                + stats.num_exact,
+            5
+        );
+        assert!(stats.pruned_by_endpoints >= 2);
+        assert!(stats.num_exact < 5);
+    }
+}
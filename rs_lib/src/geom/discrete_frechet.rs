@@ -0,0 +1,99 @@
+use itertools::Itertools;
+use nalgebra::Point;
+
+use crate::geom::curve::Curve;
+use crate::geom::Dist;
+
+pub struct DiscreteFrechet {
+    pub dist: Dist,
+    /// Pairs of vertex indices `(i, j)` into the first and second curve, in
+    /// order of traversal.
+    pub coupling: Vec<(usize, usize)>,
+}
+
+/// Computes the discrete Fréchet distance between the vertex sequences of the
+/// two curves, together with an optimal coupling (Eiter & Mannila, 1994).
+pub fn discrete_frechet<const D: usize>(
+    curves: [&Curve<D>; 2],
+) -> DiscreteFrechet {
+    discrete_frechet_by(
+        curves.map(|curve| curve.points().as_slice()),
+        |p, q| (p - q).norm(),
+    )
+}
+
+/// Computes the discrete Fréchet distance between the two point sequences,
+/// where `dist` measures the distance between a pair of points.
+pub fn discrete_frechet_by<const D: usize>(
+    points: [&[Point<Dist, D>]; 2],
+    dist: impl Fn(&Point<Dist, D>, &Point<Dist, D>) -> Dist,
+) -> DiscreteFrechet {
+    let [points_1, points_2] = points;
+    let (n, m) = (points_1.len(), points_2.len());
+
+    // Coupling distance of the optimal coupling of the prefixes ending at
+    // `(i, j)`, stored at index `i * m + j`
+    let mut coupling_dists = vec![Dist::INFINITY; n * m];
+
+    for (i, j) in Itertools::cartesian_product(0..n, 0..m) {
+        let dist = dist(&points_1[i], &points_2[j]);
+        let prev_dist = match (i, j) {
+            (0, 0) => Dist::NEG_INFINITY,
+            (0, _) => coupling_dists[j - 1],
+            (_, 0) => coupling_dists[(i - 1) * m],
+            (_, _) => coupling_dists[(i - 1) * m + j - 1]
+                .min(coupling_dists[(i - 1) * m + j])
+                .min(coupling_dists[i * m + j - 1]),
+        };
+        coupling_dists[i * m + j] = dist.max(prev_dist);
+    }
+
+    // Walk back from the end, preferring diagonal steps on ties
+    let mut coupling = vec![(n - 1, m - 1)];
+    let (mut i, mut j) = (n - 1, m - 1);
+    while (i, j) != (0, 0) {
+        (i, j) = match (i, j) {
+            (0, _) => (0, j - 1),
+            (_, 0) => (i - 1, 0),
+            (_, _) => [(i - 1, j - 1), (i - 1, j), (i, j - 1)]
+                .into_iter()
+                .min_by(|&(i1, j1), &(i2, j2)| {
+                    Dist::total_cmp(
+                        &coupling_dists[i1 * m + j1],
+                        &coupling_dists[i2 * m + j2],
+                    )
+                })
+                .unwrap(),
+        };
+        coupling.push((i, j));
+    }
+    coupling.reverse();
+
+    DiscreteFrechet {
+        dist: coupling_dists[n * m - 1],
+        coupling,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn discrete_coupling() {
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+        ]);
+        let curve_2 =
+            Curve::from_points(vec![point![0.0, 1.0], point![2.0, 1.0]]);
+
+        let result = discrete_frechet([&curve_1, &curve_2]);
+        assert_relative_eq!(result.dist, Dist::sqrt(2.0));
+        assert_eq!(result.coupling, vec![(0, 0), (1, 0), (2, 1)]);
+    }
+}
@@ -0,0 +1,150 @@
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::Dist;
+
+#[derive(Default, Deserialize)]
+pub struct DtwOptions {
+    /// Radius of the Sakoe–Chiba band, in vertices of the second curve around
+    /// the (rescaled) diagonal.
+    pub band_radius: Option<Dist>,
+    /// Maximum slope (at least 1) of the Itakura parallelogram.
+    pub itakura_slope: Option<Dist>,
+}
+
+impl DtwOptions {
+    /// Returns whether the cell `(i, j)` of an `n` by `m` cost matrix lies
+    /// within the global constraints.
+    fn in_window(
+        &self,
+        (i, j): (usize, usize),
+        (n, m): (usize, usize),
+    ) -> bool {
+        // Normalized coordinates of the cell, with the diagonal at `x == y`. A
+        // curve with a single vertex doesn't constrain the other.
+        let x = (n > 1).then(|| i as Dist / (n - 1) as Dist);
+        let y = (m > 1).then(|| j as Dist / (m - 1) as Dist);
+        let (x, y) = match (x, y) {
+            (Some(x), Some(y)) => (x, y),
+            (Some(x), None) => (x, x),
+            (None, Some(y)) => (y, y),
+            (None, None) => (0., 0.),
+        };
+
+        let in_band = self.band_radius.is_none_or(|radius| {
+            let diagonal_j = x * (m - 1) as Dist;
+            (j as Dist - diagonal_j).abs() <= radius
+        });
+
+        let in_parallelogram = self.itakura_slope.is_none_or(|slope| {
+            y <= slope * x
+                && y >= x / slope
+                && y >= 1. - slope * (1. - x)
+                && y <= 1. - (1. - x) / slope
+        });
+
+        in_band && in_parallelogram
+    }
+}
+
+pub struct Dtw {
+    /// Sum of the distances between the matched vertices
+    pub cost: Dist,
+    /// Pairs of vertex indices `(i, j)` into the first and second curve, in
+    /// order of traversal.
+    pub path: Vec<(usize, usize)>,
+}
+
+/// Computes the dynamic time warping distance between the vertex sequences of
+/// the two curves, together with an optimal warping path. Returns `None` if
+/// the constraints in `options` exclude every warping path.
+pub fn dtw<const D: usize>(
+    curves: [&Curve<D>; 2],
+    options: &DtwOptions,
+) -> Option<Dtw> {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+    let (n, m) = (points_1.len(), points_2.len());
+
+    // Cost of the optimal warping path of the prefixes ending at `(i, j)`,
+    // stored at index `i * m + j`
+    let mut costs = vec![Dist::INFINITY; n * m];
+
+    for (i, j) in Itertools::cartesian_product(0..n, 0..m) {
+        if !options.in_window((i, j), (n, m)) {
+            continue;
+        }
+
+        let cost = (points_1[i] - points_2[j]).norm();
+        let prev_cost = match (i, j) {
+            (0, 0) => 0.,
+            (0, _) => costs[j - 1],
+            (_, 0) => costs[(i - 1) * m],
+            (_, _) => costs[(i - 1) * m + j - 1]
+                .min(costs[(i - 1) * m + j])
+                .min(costs[i * m + j - 1]),
+        };
+        costs[i * m + j] = cost + prev_cost;
+    }
+
+    if costs[n * m - 1].is_infinite() {
+        return None;
+    }
+
+    // Walk back from the end, preferring diagonal steps on ties
+    let mut path = vec![(n - 1, m - 1)];
+    let (mut i, mut j) = (n - 1, m - 1);
+    while (i, j) != (0, 0) {
+        (i, j) = match (i, j) {
+            (0, _) => (0, j - 1),
+            (_, 0) => (i - 1, 0),
+            (_, _) => [(i - 1, j - 1), (i - 1, j), (i, j - 1)]
+                .into_iter()
+                .min_by(|&(i1, j1), &(i2, j2)| {
+                    Dist::total_cmp(&costs[i1 * m + j1], &costs[i2 * m + j2])
+                })
+                .unwrap(),
+        };
+        path.push((i, j));
+    }
+    path.reverse();
+
+    Some(Dtw {
+        cost: costs[n * m - 1],
+        path,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn warping_path() {
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+        ]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+        ]);
+
+        let result = dtw([&curve_1, &curve_2], &DtwOptions::default()).unwrap();
+        assert_relative_eq!(result.cost, 0.0);
+        assert_eq!(result.path, vec![(0, 0), (0, 1), (1, 2), (2, 3)]);
+
+        // A band of radius zero around the diagonal forbids the warping
+        let options = DtwOptions {
+            band_radius: Some(0.),
+            ..Default::default()
+        };
+        assert!(dtw([&curve_1, &curve_2], &options).is_none());
+    }
+}
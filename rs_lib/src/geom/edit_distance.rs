@@ -0,0 +1,173 @@
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::Dist;
+
+#[derive(Serialize)]
+pub struct Alignment {
+    /// Length of the common subsequence for LCSS, edit cost for EDR and ERP
+    pub score: Dist,
+    /// Pairs of vertex indices `(i, j)` into the first and second curve that
+    /// are aligned to each other, in order. All other vertices are aligned to
+    /// gaps.
+    pub pairs: Vec<(usize, usize)>,
+}
+
+/// Returns whether two points match under the threshold `epsilon`, which is
+/// applied to each coordinate separately as in the original definitions of
+/// LCSS and EDR.
+fn points_match(p: &Point<Dist, 2>, q: &Point<Dist, 2>, epsilon: Dist) -> bool {
+    (p - q).amax() <= epsilon
+}
+
+/// Computes the cheapest alignment of two sequences of lengths `n` and `m`,
+/// where aligning `i` to `j` costs `pair_cost(i, j)` and aligning `i` or `j`
+/// to a gap costs `gap_cost_1(i)` or `gap_cost_2(j)`.
+fn align(
+    (n, m): (usize, usize),
+    pair_cost: impl Fn(usize, usize) -> Dist,
+    gap_cost_1: impl Fn(usize) -> Dist,
+    gap_cost_2: impl Fn(usize) -> Dist,
+) -> (Dist, Vec<(usize, usize)>) {
+    // Cost of the cheapest alignment of the first `i` and `j` vertices,
+    // stored at index `i * (m + 1) + j`
+    let idx = |i: usize, j: usize| i * (m + 1) + j;
+    let mut costs = vec![0.; (n + 1) * (m + 1)];
+
+    for (i, j) in Itertools::cartesian_product(0..=n, 0..=m) {
+        costs[idx(i, j)] = match (i, j) {
+            (0, 0) => 0.,
+            (0, _) => costs[idx(0, j - 1)] + gap_cost_2(j - 1),
+            (_, 0) => costs[idx(i - 1, 0)] + gap_cost_1(i - 1),
+            (_, _) => (costs[idx(i - 1, j - 1)] + pair_cost(i - 1, j - 1))
+                .min(costs[idx(i - 1, j)] + gap_cost_1(i - 1))
+                .min(costs[idx(i, j - 1)] + gap_cost_2(j - 1)),
+        };
+    }
+
+    // Walk back from the end, preferring aligned pairs on ties
+    let mut pairs = vec![];
+    let (mut i, mut j) = (n, m);
+    while (i, j) != (0, 0) {
+        let cost = costs[idx(i, j)];
+        (i, j) = if i > 0
+            && j > 0
+            && cost == costs[idx(i - 1, j - 1)] + pair_cost(i - 1, j - 1)
+        {
+            pairs.push((i - 1, j - 1));
+            (i - 1, j - 1)
+        } else if i > 0 && cost == costs[idx(i - 1, j)] + gap_cost_1(i - 1) {
+            (i - 1, j)
+        } else {
+            (i, j - 1)
+        };
+    }
+    pairs.reverse();
+
+    (costs[idx(n, m)], pairs)
+}
+
+/// Computes the longest common subsequence of the vertex sequences of the two
+/// curves, where two vertices match if they are within `epsilon` of each
+/// other in every coordinate (Vlachos et al., 2002).
+pub fn lcss(curves: [&Curve; 2], epsilon: Dist) -> Alignment {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+    let (n, m) = (points_1.len(), points_2.len());
+
+    // Every vertex outside of the common subsequence is aligned to a gap
+    let (cost, pairs) = align(
+        (n, m),
+        |i, j| {
+            if points_match(&points_1[i], &points_2[j], epsilon) {
+                0.
+            } else {
+                Dist::INFINITY
+            }
+        },
+        |_| 1.,
+        |_| 1.,
+    );
+
+    Alignment {
+        score: ((n + m) as Dist - cost) / 2.,
+        pairs,
+    }
+}
+
+/// Computes the edit distance on real sequences between the vertex sequences
+/// of the two curves, where substituting a vertex costs nothing if it matches
+/// within `epsilon` in every coordinate, and 1 otherwise (Chen et al., 2005).
+pub fn edr(curves: [&Curve; 2], epsilon: Dist) -> Alignment {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+
+    let (score, pairs) = align(
+        (points_1.len(), points_2.len()),
+        |i, j| {
+            if points_match(&points_1[i], &points_2[j], epsilon) {
+                0.
+            } else {
+                1.
+            }
+        },
+        |_| 1.,
+        |_| 1.,
+    );
+
+    Alignment { score, pairs }
+}
+
+/// Computes the edit distance with real penalty between the vertex sequences
+/// of the two curves, where aligning two vertices costs their distance and
+/// aligning a vertex to a gap costs its distance to the constant `gap`
+/// (Chen & Ng, 2004).
+pub fn erp(curves: [&Curve; 2], gap: &Point<Dist, 2>) -> Alignment {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+
+    let (score, pairs) = align(
+        (points_1.len(), points_2.len()),
+        |i, j| (points_1[i] - points_2[j]).norm(),
+        |i| (points_1[i] - gap).norm(),
+        |j| (points_2[j] - gap).norm(),
+    );
+
+    Alignment { score, pairs }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn alignments() {
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+        ]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.1],
+            point![5.0, 5.0],
+            point![2.0, 0.1],
+        ]);
+        let curves = [&curve_1, &curve_2];
+
+        let result = lcss(curves, 0.2);
+        assert_relative_eq!(result.score, 2.0);
+        assert_eq!(result.pairs, vec![(0, 0), (2, 2)]);
+
+        let result = edr(curves, 0.2);
+        assert_relative_eq!(result.score, 1.0);
+        assert_eq!(result.pairs, vec![(0, 0), (1, 1), (2, 2)]);
+
+        // Dropping both middle vertices costs 1 + 5√2, substituting them only
+        // their distance √41
+        let result = erp(curves, &point![0.0, 0.0]);
+        assert_relative_eq!(result.score, 0.2 + Dist::sqrt(41.0));
+        assert_eq!(result.pairs, vec![(0, 0), (1, 1), (2, 2)]);
+    }
+}
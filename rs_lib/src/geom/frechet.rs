@@ -0,0 +1,570 @@
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::curve_dist_fn::CurveDistFn;
+use crate::geom::free_space::{FreeCell, FreeSpaceDiagram};
+use crate::geom::line_segment::LineSegment;
+use crate::geom::Dist;
+
+/// Interval `[lo, hi]` in the local `[0, 1]` parameters of a segment, or `None`
+/// if it is empty.
+pub type Interval = Option<[Dist; 2]>;
+
+/// Relative amount by which candidate distances are inflated before being
+/// decided, so that rounding errors at the exact critical values don't cause
+/// the decision procedure to reject them.
+pub fn tolerance() -> Dist {
+    Dist::EPSILON.sqrt()
+}
+
+/// Returns `epsilon` inflated by the tolerance, relative both to itself and to
+/// `scale`, the largest distance between the curves. Rounding errors in the
+/// free space grow with the coordinates rather than with epsilon, so small
+/// distances between large curves would otherwise be rejected.
+fn inflate(epsilon: Dist, scale: Dist) -> Dist {
+    epsilon * (1. + tolerance()) + scale * tolerance()
+}
+
+/// Returns the line segments of the curve, where a curve consisting of a single
+/// point is treated as a single degenerate segment.
+pub fn segments<const D: usize>(curve: &Curve<D>) -> Vec<LineSegment<Dist, D>> {
+    match curve.points().as_slice() {
+        [point] => vec![(*point, *point).into()],
+        _ => curve.line_segments().collect(),
+    }
+}
+
+/// Returns the part of the free interval `free` that can be reached from a
+/// reachable interval on the opposite boundary of a cell (`opposite`), or from
+/// a reachable interval on the boundary crossing it (`crossing`).
+fn propagate(
+    free: Interval,
+    crossing: Interval,
+    opposite: Interval,
+) -> Interval {
+    if crossing.is_some() {
+        return free;
+    }
+
+    let [opposite_lo, _] = opposite?;
+    let [free_lo, free_hi] = free?;
+    let lo = opposite_lo.max(free_lo);
+    if lo <= free_hi {
+        Some([lo, free_hi])
+    } else {
+        None
+    }
+}
+
+/// Where monotone paths through the free-space diagram may start.
+#[derive(Copy, Clone, PartialEq)]
+enum Start {
+    /// Only at the bottom-left corner, i.e. both curves are matched from their
+    /// start
+    Origin,
+    /// Anywhere on the left boundary, i.e. the first curve may be matched to a
+    /// subcurve of the second
+    LeftBoundary,
+}
+
+/// Reachable parts of the free-space diagram, where cell `(i, j)` is the
+/// product of the `i`-th segment of the first curve and the `j`-th segment of
+/// the second. Intervals are given in the local `[0, 1]` parameters of the
+/// segments.
+struct Reachability {
+    n: usize,
+    m: usize,
+    start: Start,
+    /// Reachable intervals on the vertical boundary `x = i` of the cells in row
+    /// `j`, indexed `[i][j]`
+    vertical: Vec<Vec<Interval>>,
+    /// Reachable intervals on the horizontal boundary `y = j` of the cells in
+    /// column `i`, indexed `[i][j]`
+    horizontal: Vec<Vec<Interval>>,
+}
+
+impl Reachability {
+    /// Propagates reachable intervals through the free-space diagram (Alt &
+    /// Godau, 1995).
+    fn new<const D: usize>(
+        curves: [&Curve<D>; 2],
+        epsilon: Dist,
+        start: Start,
+    ) -> Self {
+        Self::from_free_space(&FreeSpaceDiagram::new(curves, epsilon), start)
+    }
+
+    fn from_free_space(free_space: &FreeSpaceDiagram, start: Start) -> Self {
+        let (n, m) = free_space.shape();
+
+        let mut vertical: Vec<Vec<Interval>> = vec![vec![None; m]; n + 1];
+        let mut horizontal: Vec<Vec<Interval>> = vec![vec![None; m + 1]; n];
+
+        for i in 0..n {
+            let reachable = i == 0
+                || matches!(horizontal[i - 1][0], Some([_, hi]) if hi >= 1.);
+            horizontal[i][0] = free_space
+                .horizontal(i, 0)
+                .filter(|&[lo, _]| reachable && lo <= 0.);
+        }
+        for j in 0..m {
+            let free = free_space.vertical(0, j);
+            vertical[0][j] = match start {
+                Start::Origin => {
+                    let reachable = j == 0
+                        || matches!(vertical[0][j - 1], Some([_, hi]) if hi >= 1.);
+                    free.filter(|&[lo, _]| reachable && lo <= 0.)
+                }
+                Start::LeftBoundary => free,
+            };
+        }
+
+        for (i, j) in Itertools::cartesian_product(0..n, 0..m) {
+            let left = vertical[i][j];
+            let bottom = horizontal[i][j];
+            let FreeCell { right, top, .. } = free_space.cell(i, j);
+
+            vertical[i + 1][j] = propagate(right, bottom, left);
+            horizontal[i][j + 1] = propagate(top, left, bottom);
+        }
+
+        Self {
+            n,
+            m,
+            start,
+            vertical,
+            horizontal,
+        }
+    }
+
+    /// Returns whether the top-right corner of the diagram is reachable.
+    fn reaches_end(&self) -> bool {
+        let (n, m) = (self.n, self.m);
+        matches!(self.vertical[n][m - 1], Some([_, hi]) if hi >= 1.)
+            || matches!(self.horizontal[n - 1][m], Some([_, hi]) if hi >= 1.)
+    }
+
+    /// Returns the lowest reachable point on the right boundary of the
+    /// diagram, as `(j, t)` at parameter `t` on the `j`-th segment of the
+    /// second curve.
+    fn lowest_reachable_end(&self) -> Option<(usize, Dist)> {
+        (0..self.m)
+            .find_map(|j| self.vertical[self.n][j].map(|[lo, _]| (j, lo)))
+    }
+
+    /// Returns a monotone path through the free space to the given reachable
+    /// point `(j, t)` on the right boundary of the diagram, as a sequence of
+    /// points `[(i, s), (j, t)]` at parameter `s` on the `i`-th segment of the
+    /// first curve and parameter `t` on the `j`-th segment of the second.
+    /// Consecutive points lie in a common cell, or on the boundary of the
+    /// diagram.
+    fn monotone_path(
+        &self,
+        (end_j, end_t): (usize, Dist),
+    ) -> Option<Vec<[(usize, Dist); 2]>> {
+        // Walk back from the end, keeping the current point on the top or
+        // right boundary of cell `(i, j)`. Since the free space within a cell
+        // is convex, any reachable point on the left or bottom boundary that
+        // precedes the current point can be connected to it by a straight
+        // line.
+        let (mut i, mut j) = (self.n - 1, end_j);
+        let (mut x, mut y) = (1., end_t);
+        let mut path = vec![[(i, x), (j, y)]];
+
+        let reached_left = loop {
+            if let Some([_, hi]) =
+                self.vertical[i][j].filter(|&[lo, _]| lo <= y)
+            {
+                (x, y) = (0., hi.min(y));
+                path.push([(i, x), (j, y)]);
+                if i == 0 {
+                    break true;
+                }
+                (i, x) = (i - 1, 1.);
+            } else if let Some([_, hi]) =
+                self.horizontal[i][j].filter(|&[lo, _]| lo <= x)
+            {
+                (x, y) = (hi.min(x), 0.);
+                path.push([(i, x), (j, y)]);
+                if j == 0 {
+                    break false;
+                }
+                (j, y) = (j - 1, 1.);
+            } else {
+                return None;
+            }
+        };
+
+        // Unless the path may start anywhere on the left boundary, the
+        // remaining part of the left or bottom boundary of the diagram is
+        // entirely reachable from the origin
+        if !reached_left || self.start == Start::Origin {
+            path.push([(0, 0.), (0, 0.)]);
+        }
+        path.reverse();
+        path.dedup();
+        Some(path)
+    }
+}
+
+/// Propagates the reachable interval `bottom` on the bottom boundary of cell
+/// `first` of the free-space diagram of a curve and the segment from `start`
+/// to `end`, through its only row of cells. Calls `reach` with the index and
+/// reachable interval on the top boundary of each cell that is reached, in
+/// the local parameters of that segment of the curve.
+///
+/// The curve is given by its `points` and its [`segments`]. Cells before
+/// `first` cannot be reached, and propagation stops at the first cell that
+/// cannot be entered from the left, whose index is returned. Cells between
+/// `first` and that index are entered from the left, so propagating from the
+/// bottom of any of them can reach nothing more.
+pub(crate) fn propagate_row<const D: usize>(
+    points: &[Point<Dist, D>],
+    curve_segments: &[LineSegment<Dist, D>],
+    [start, end]: [&Point<Dist, D>; 2],
+    epsilon: Dist,
+    (first, bottom): (usize, [Dist; 2]),
+    mut reach: impl FnMut(usize, [Dist; 2]),
+) -> usize {
+    let vertex = |i: usize| &points[i.min(points.len() - 1)];
+    let segment = LineSegment::from((*start, *end));
+
+    let mut bottom: Interval = Some(bottom);
+    let mut left: Interval = None;
+    for (i, curve_segment) in curve_segments.iter().enumerate().skip(first) {
+        let free_right = segment.ball_intersection(vertex(i + 1), epsilon);
+        let free_top = curve_segment.ball_intersection(end, epsilon);
+
+        if let Some(top) = propagate(free_top, left, bottom) {
+            reach(i, top);
+        }
+        left = propagate(free_right, bottom, left);
+        bottom = None;
+
+        if left.is_none() {
+            return i + 1;
+        }
+    }
+    curve_segments.len()
+}
+
+/// Decides whether the Fréchet distance between the two curves is at most
+/// `epsilon`, by propagating reachable intervals through the free-space
+/// diagram.
+pub fn frechet_decide<const D: usize>(
+    curves: [&Curve<D>; 2],
+    epsilon: Dist,
+) -> bool {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+
+    if (points_1.first().unwrap() - points_2.first().unwrap()).norm() > epsilon
+        || (points_1.last().unwrap() - points_2.last().unwrap()).norm()
+            > epsilon
+    {
+        return false;
+    }
+
+    Reachability::new(curves, epsilon, Start::Origin).reaches_end()
+}
+
+/// Returns the sorted values of epsilon at which the free-space diagram can
+/// change combinatorially, restricted to the range between `lower_bound` and
+/// the largest distance between the curves.
+fn critical_values<const D: usize>(
+    curves: [&Curve<D>; 2],
+    lower_bound: Dist,
+) -> Vec<Dist> {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+    let [segments_1, segments_2] = curves.map(segments);
+
+    let upper_bound = CurveDistFn::new(curves).max_dist();
+
+    let vertex_segment_dists =
+        |points: &[Point<Dist, D>], segments: &[LineSegment<Dist, D>]| {
+            Itertools::cartesian_product(points.iter(), segments.iter())
+                .map(|(point, segment)| {
+                    segment.point_dist_squared(point).sqrt()
+                })
+                .collect_vec()
+        };
+
+    let bisector_dists =
+        |points: &[Point<Dist, D>], segments: &[LineSegment<Dist, D>]| {
+            Itertools::cartesian_product(
+                points.iter().tuple_combinations::<(_, _)>(),
+                segments.iter(),
+            )
+            .filter_map(|((a, b), segment)| {
+                segment
+                    .bisector_intersection(a, b)
+                    .map(|t| (segment.point_at(t) - a).norm())
+            })
+            .collect_vec()
+        };
+
+    let mut candidates = [lower_bound, upper_bound]
+        .into_iter()
+        // Type (b): a passage opens up on a cell boundary
+        .chain(vertex_segment_dists(points_1, &segments_2))
+        .chain(vertex_segment_dists(points_2, &segments_1))
+        // Type (c): a horizontal or vertical passage opens up between cells
+        .chain(bisector_dists(points_1, &segments_2))
+        .chain(bisector_dists(points_2, &segments_1))
+        .filter(|&dist| lower_bound <= dist && dist <= upper_bound)
+        .collect_vec();
+
+    candidates.sort_by(Dist::total_cmp);
+    candidates.dedup();
+    candidates
+}
+
+/// Computes the (continuous) Fréchet distance between the two curves, by
+/// searching for the smallest critical value accepted by [`frechet_decide`].
+pub fn frechet_dist<const D: usize>(curves: [&Curve<D>; 2]) -> Dist {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+
+    // Type (a): distances between the start and end points
+    let lower_bound = Dist::max(
+        (points_1.first().unwrap() - points_2.first().unwrap()).norm(),
+        (points_1.last().unwrap() - points_2.last().unwrap()).norm(),
+    );
+    let candidates = critical_values(curves, lower_bound);
+    let scale = candidates.last().copied().unwrap_or(lower_bound);
+
+    let idx = candidates.partition_point(|&candidate| {
+        !frechet_decide(curves, inflate(candidate, scale))
+    });
+    candidates[idx.min(candidates.len() - 1)]
+}
+
+/// Returns a monotone matching between the two curves whose leash length never
+/// exceeds `epsilon`, as a polyline in parameter space from `(0, 0)` to
+/// `(L1, L2)`, or `None` if no such matching exists.
+pub fn frechet_matching<const D: usize>(
+    curves: [&Curve<D>; 2],
+    epsilon: Dist,
+) -> Option<Vec<Point<Dist, 2>>> {
+    let reachability = Reachability::new(curves, epsilon, Start::Origin);
+    if !reachability.reaches_end() {
+        return None;
+    }
+    let path = reachability.monotone_path((reachability.m - 1, 1.))?;
+
+    Some(
+        path.into_iter()
+            .map(|[(i, s), (j, t)]| {
+                Point::from([
+                    curves[0].segment_param_to_length(i, s),
+                    curves[1].segment_param_to_length(j, t),
+                ])
+            })
+            .collect(),
+    )
+}
+
+/// Returns, for every cell `(i, j)` of the free-space diagram, the lowest
+/// points on its bottom and left boundary that are reachable from `(0, 0)` by
+/// monotone paths through the free space, as parameters on the `i`-th segment
+/// of the first curve and the `j`-th segment of the second, indexed `[i][j]`.
+///
+/// Since the free space within a cell is convex, a free point in the cell is
+/// reachable if and only if it lies right of the former or above the latter.
+pub fn reachable_entries(
+    free_space: &FreeSpaceDiagram,
+) -> Vec<Vec<[Option<Dist>; 2]>> {
+    let reachability = Reachability::from_free_space(free_space, Start::Origin);
+    let lo = |interval: Interval| interval.map(|[lo, _]| lo);
+
+    (0..reachability.n)
+        .map(|i| {
+            (0..reachability.m)
+                .map(|j| {
+                    [
+                        lo(reachability.horizontal[i][j]),
+                        lo(reachability.vertical[i][j]),
+                    ]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns a monotone matching between the two curves that realizes their
+/// Fréchet distance, as a polyline in parameter space.
+pub fn optimal_frechet_matching<const D: usize>(
+    curves: [&Curve<D>; 2],
+) -> Vec<Point<Dist, 2>> {
+    let scale = CurveDistFn::new(curves).max_dist();
+    let epsilon = inflate(frechet_dist(curves), scale);
+    frechet_matching(curves, epsilon)
+        .expect("Fréchet distance should admit a matching")
+}
+
+#[derive(Serialize)]
+pub struct PartialFrechet {
+    pub dist: Dist,
+    /// Arc length on the second curve where the matched subcurve starts
+    pub start_length: Dist,
+    /// Arc length on the second curve where the matched subcurve ends
+    pub end_length: Dist,
+}
+
+/// Finds the subcurve of the second curve with the smallest Fréchet distance
+/// to the entire first curve, by allowing monotone paths through the
+/// free-space diagram to start anywhere on its left boundary and end anywhere
+/// on its right boundary.
+pub fn partial_frechet<const D: usize>(
+    curves: [&Curve<D>; 2],
+) -> PartialFrechet {
+    let [curve_1, curve_2] = curves;
+    let segments_2 = segments(curve_2);
+
+    // Both endpoints of the first curve have to be matched somewhere
+    let dist_to_curve_2 = |point: &Point<Dist, D>| {
+        segments_2
+            .iter()
+            .map(|segment| segment.point_dist_squared(point).sqrt())
+            .fold(Dist::INFINITY, Dist::min)
+    };
+    let lower_bound = Dist::max(
+        dist_to_curve_2(curve_1.points().first().unwrap()),
+        dist_to_curve_2(curve_1.points().last().unwrap()),
+    );
+
+    let candidates = critical_values(curves, lower_bound);
+    let scale = candidates.last().copied().unwrap_or(lower_bound);
+    let reachability = |epsilon: Dist| {
+        Reachability::new(curves, inflate(epsilon, scale), Start::LeftBoundary)
+    };
+
+    let idx = candidates.partition_point(|&candidate| {
+        reachability(candidate).lowest_reachable_end().is_none()
+    });
+    let dist = candidates[idx.min(candidates.len() - 1)];
+
+    let reachability = reachability(dist);
+    let path = reachability
+        .lowest_reachable_end()
+        .and_then(|end| reachability.monotone_path(end))
+        .expect("partial Fréchet distance should admit a matching");
+
+    let [_, (start_j, start_t)] = *path.first().unwrap();
+    let [_, (end_j, end_t)] = *path.last().unwrap();
+
+    PartialFrechet {
+        dist,
+        start_length: curve_2.segment_param_to_length(start_j, start_t),
+        end_length: curve_2.segment_param_to_length(end_j, end_t),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn parallel_lines() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 =
+            Curve::from_points(vec![point![0.0, 1.0], point![2.0, 1.0]]);
+
+        assert!(frechet_decide([&curve_1, &curve_2], 1.0 + 1e-4));
+        assert!(!frechet_decide([&curve_1, &curve_2], 1.0 - 1e-4));
+        assert_relative_eq!(frechet_dist([&curve_1, &curve_2]), 1.0);
+    }
+
+    #[test]
+    fn matching_is_monotone() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.5, 0.0],
+            point![0.5, 0.0],
+            point![2.0, 0.0],
+        ]);
+
+        let matching = optimal_frechet_matching([&curve_1, &curve_2]);
+        assert_relative_eq!(*matching.first().unwrap(), point![0.0, 0.0]);
+        assert_relative_eq!(*matching.last().unwrap(), point![2.0, 4.0]);
+        assert!(matching
+            .iter()
+            .tuple_windows()
+            .all(|(p1, p2)| p1.x <= p2.x && p1.y <= p2.y));
+
+        assert!(frechet_matching([&curve_1, &curve_2], 0.4).is_none());
+    }
+
+    #[test]
+    fn reachable_cells() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 1.0],
+            point![1.0, 1.0],
+            point![2.0, 1.0],
+        ]);
+
+        // The left boundary is only free up to 0.75 along the first segment
+        // of the second curve, so the second cell is only entered from below
+        let free_space = FreeSpaceDiagram::new([&curve_1, &curve_2], 1.25);
+        let entries = reachable_entries(&free_space);
+        assert_eq!(entries[0][0], [Some(0.0), Some(0.0)]);
+        let [bottom, left] = entries[0][1];
+        assert_relative_eq!(bottom.unwrap(), 0.125);
+        assert_eq!(left, None);
+
+        // The start points are too far apart
+        let free_space = FreeSpaceDiagram::new([&curve_1, &curve_2], 0.9);
+        assert_eq!(reachable_entries(&free_space), vec![vec![[None, None]; 2]]);
+    }
+
+    #[test]
+    fn partial_matching() {
+        let curve_1 =
+            Curve::from_points(vec![point![1.0, 0.5], point![2.0, 0.5]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![3.0, 0.0],
+            point![3.0, 3.0],
+        ]);
+
+        let result = partial_frechet([&curve_1, &curve_2]);
+        assert_relative_eq!(result.dist, 0.5, max_relative = 1e-4);
+        // The free intervals touch the matched endpoints tangentially, so the
+        // tolerance on epsilon shows up in its square root here
+        assert_relative_eq!(result.start_length, 1.0, epsilon = 0.05);
+        assert_relative_eq!(result.end_length, 2.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn backtracking() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.5, 0.0],
+            point![0.5, 0.0],
+            point![2.0, 0.0],
+        ]);
+
+        // The Fréchet distance is determined by the backtracking part of the
+        // second curve, which is a critical value of type (c)
+        assert_relative_eq!(
+            frechet_dist([&curve_1, &curve_2]),
+            0.5,
+            max_relative = 1e-4
+        );
+        assert_relative_eq!(
+            frechet_dist([&curve_2, &curve_1]),
+            0.5,
+            max_relative = 1e-4
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use itertools::Itertools;
+use nalgebra::{Point, Vector2};
+use serde::Deserialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::{frechet_dist, optimal_frechet_matching};
+use crate::geom::Dist;
+use crate::math::function::Function;
+use crate::traits::mix::{InverseMix, Mix};
+
+const MAX_ITERATIONS: usize = 20;
+/// Number of Bădoiu–Clarkson iterations used to approximate the center of the
+/// smallest enclosing circle.
+const ENCLOSING_CIRCLE_ITERATIONS: usize = 32;
+/// Largest number of Weiszfeld iterations used to approximate the geometric
+/// median.
+const MAX_WEISZFELD_ITERATIONS: usize = 100;
+
+/// Aggregate of the Fréchet distances to the input curves that the
+/// representative curve minimizes.
+#[derive(Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeanObjective {
+    /// Maximum distance, giving a center curve
+    Center,
+    /// Sum of distances, giving a median curve
+    Median,
+}
+
+impl MeanObjective {
+    fn cost(self, curve: &Curve, inputs: &[&Curve]) -> Dist {
+        let dists = inputs.iter().map(|input| frechet_dist([curve, input]));
+        match self {
+            Self::Center => dists.fold(0., Dist::max),
+            Self::Median => dists.sum(),
+        }
+    }
+
+    /// Returns the point that best represents the `points` matched to a single
+    /// vertex of the representative curve, which is currently at `vertex`.
+    fn representative(
+        self,
+        points: &[Point<Dist, 2>],
+        vertex: Point<Dist, 2>,
+    ) -> Point<Dist, 2> {
+        let centroid = Point::from(
+            points
+                .iter()
+                .fold(Vector2::zeros(), |sum, point| sum + point.coords)
+                / points.len() as Dist,
+        );
+
+        match self {
+            // Bădoiu–Clarkson: repeatedly step towards the farthest point
+            Self::Center => (1..=ENCLOSING_CIRCLE_ITERATIONS).fold(
+                centroid,
+                |center, iteration| {
+                    let farthest = points
+                        .iter()
+                        .max_by(|p1, p2| {
+                            Dist::total_cmp(
+                                &(*p1 - center).norm_squared(),
+                                &(*p2 - center).norm_squared(),
+                            )
+                        })
+                        .unwrap();
+                    center.mix(*farthest, 1. / (iteration + 1) as Dist)
+                },
+            ),
+            Self::Median => {
+                let scale = points
+                    .iter()
+                    .map(|point| (point - centroid).norm())
+                    .fold(0., Dist::max);
+                let mut median = vertex;
+                for _ in 0..MAX_WEISZFELD_ITERATIONS {
+                    let next = weiszfeld_step(points, median, scale);
+                    let step = (next - median).norm();
+                    median = next;
+                    if step <= scale * 1e-6 {
+                        break;
+                    }
+                }
+                median
+            }
+        }
+    }
+}
+
+/// Moves `estimate` towards the geometric median of `points`, using the
+/// Weiszfeld iteration as modified by Vardi and Zhang, so that an estimate at
+/// one of the points only stays there if that point is the median. Points
+/// within a fraction of `scale` of the estimate count as coinciding with it.
+fn weiszfeld_step(
+    points: &[Point<Dist, 2>],
+    estimate: Point<Dist, 2>,
+    scale: Dist,
+) -> Point<Dist, 2> {
+    let mut num_coinciding = 0;
+    let mut total_weight = 0.;
+    let mut weighted_sum = Vector2::zeros();
+    let mut pull = Vector2::zeros();
+    for point in points {
+        let dist = (point - estimate).norm();
+        if dist <= scale * Dist::EPSILON {
+            num_coinciding += 1;
+            continue;
+        }
+        total_weight += 1. / dist;
+        weighted_sum += point.coords / dist;
+        pull += (point - estimate) / dist;
+    }
+
+    if total_weight == 0. {
+        return estimate;
+    }
+
+    let target = Point::from(weighted_sum / total_weight);
+    if num_coinciding == 0 {
+        return target;
+    }
+
+    // The coinciding points hold the estimate back with a force of their
+    // number, against the pull of the others
+    let hold = (num_coinciding as Dist / pull.norm()).min(1.);
+    target.mix(estimate, hold)
+}
+
+pub struct FrechetMean {
+    /// Representative curve with at most the requested number of vertices
+    pub curve: Curve,
+    /// Maximum or sum of the Fréchet distances from `curve` to the inputs
+    pub cost: Dist,
+}
+
+/// Returns `num_vertices` points at equal arc length fractions along the
+/// curve, including its endpoints.
+fn resample(curve: &Curve, num_vertices: usize) -> Curve {
+    let last_idx = (num_vertices - 1).max(1) as Dist;
+    Curve::from_points(
+        (0..num_vertices)
+            .map(|idx| {
+                curve.eval(idx as Dist / last_idx * curve.total_length())
+            })
+            .collect(),
+    )
+}
+
+/// Returns the second parameter of the first point on the monotone `matching`
+/// whose first parameter is `x`.
+fn matched_length(matching: &[Point<Dist, 2>], x: Dist) -> Dist {
+    matching
+        .iter()
+        .tuple_windows()
+        .find(|(_, p2)| x <= p2.x)
+        .map(|(p1, p2)| {
+            if p1.x < p2.x {
+                p1.y.mix(p2.y, x.inverse_mix(p1.x, p2.x))
+            } else {
+                p1.y
+            }
+        })
+        .unwrap_or_else(|| matching.last().unwrap().y)
+}
+
+/// Computes a representative curve with `num_vertices` vertices that
+/// minimizes the maximum or sum of Fréchet distances to the input curves.
+///
+/// Starting from the resampled input curve with the lowest cost, the vertices
+/// are refined by alternating between computing optimal Fréchet matchings to
+/// all inputs and moving each vertex to the center (or median) of the points
+/// it is matched to, until the cost stops improving. Returns `None` if no
+/// curves are given or one of them has no points.
+pub fn frechet_mean(
+    inputs: &[&Curve],
+    objective: MeanObjective,
+    num_vertices: usize,
+) -> Option<FrechetMean> {
+    if inputs.is_empty() || inputs.iter().any(|input| input.points().is_empty())
+    {
+        return None;
+    }
+    let num_vertices = num_vertices.max(2);
+
+    let (mut curve, mut cost) = inputs
+        .iter()
+        .map(|input| {
+            let candidate = resample(input, num_vertices);
+            let cost = objective.cost(&candidate, inputs);
+            (candidate, cost)
+        })
+        .min_by(|(_, cost_1), (_, cost_2)| Dist::total_cmp(cost_1, cost_2))
+        .unwrap();
+
+    for _ in 0..MAX_ITERATIONS {
+        let matchings = inputs
+            .iter()
+            .map(|input| optimal_frechet_matching([&curve, input]))
+            .collect_vec();
+
+        let new_curve = Curve::from_points(
+            curve
+                .cumulative_lengths()
+                .iter()
+                .zip(curve.points())
+                .map(|(&length, &vertex)| {
+                    let matched_points = inputs
+                        .iter()
+                        .zip(&matchings)
+                        .map(|(input, matching)| {
+                            input.eval(matched_length(matching, length))
+                        })
+                        .collect_vec();
+                    objective.representative(&matched_points, vertex)
+                })
+                .collect(),
+        );
+        let new_cost = objective.cost(&new_curve, inputs);
+
+        if new_cost >= cost * (1. - 1e-4) {
+            if new_cost < cost {
+                (curve, cost) = (new_curve, new_cost);
+            }
+            break;
+        }
+        (curve, cost) = (new_curve, new_cost);
+    }
+
+    Some(FrechetMean { curve, cost })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn parallel_lines() {
+        let inputs = [0.0, 1.0, 4.0]
+            .map(|y| Curve::from_points(vec![point![0.0, y], point![4.0, y]]));
+        let inputs = inputs.iter().collect_vec();
+
+        let center = frechet_mean(&inputs, MeanObjective::Center, 3).unwrap();
+        assert_eq!(center.curve.points().len(), 3);
+        // The enclosing circle center is only approximated
+        assert_relative_eq!(center.cost, 2.0, epsilon = 0.05);
+
+        let median = frechet_mean(&inputs, MeanObjective::Median, 3).unwrap();
+        assert_relative_eq!(median.cost, 4.0, epsilon = 1e-2);
+        assert_relative_eq!(
+            median.curve.points()[1],
+            point![2.0, 1.0],
+            epsilon = 1e-2
+        );
+
+        // No curves to average
+        assert!(frechet_mean(&[], MeanObjective::Center, 3).is_none());
+    }
+
+    #[test]
+    fn fermat_point() {
+        // The median of the corners of this triangle is at (t, t) with
+        // 3t^2 - 12t + 8 = 0, which is not on any of the input curves
+        let corners = [point![0.0, 0.0], point![4.0, 0.0], point![0.0, 4.0]];
+        let t = (12. - Dist::sqrt(48.)) / 6.;
+
+        // Starting at a corner that is not the median
+        let median = MeanObjective::Median.representative(&corners, corners[0]);
+        assert_relative_eq!(median, point![t, t], epsilon = 1e-3);
+
+        let inputs =
+            corners.map(|p| Curve::from_points(vec![p, p + vector![2.0, 0.0]]));
+        let inputs = inputs.iter().collect_vec();
+
+        // Endpoints are always matched to endpoints, so a segment is moved
+        // onto the medians of the endpoints
+        let median = frechet_mean(&inputs, MeanObjective::Median, 2).unwrap();
+        assert_relative_eq!(
+            median.curve.points()[0],
+            point![t, t],
+            epsilon = 1e-3
+        );
+        let expected_cost = Dist::sqrt(2.) * t + 2. * Dist::hypot(4. - t, t);
+        assert_relative_eq!(median.cost, expected_cost, epsilon = 1e-3);
+    }
+}
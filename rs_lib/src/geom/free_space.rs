@@ -0,0 +1,154 @@
+use crate::geom::curve::Curve;
+use crate::geom::frechet::{segments, Interval};
+use crate::geom::Dist;
+
+/// Free intervals on the four boundaries of a cell of the free-space diagram,
+/// in the local `[0, 1]` parameters of the segments along them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FreeCell {
+    pub left: Interval,
+    pub right: Interval,
+    pub bottom: Interval,
+    pub top: Interval,
+}
+
+/// Free-space diagram of two curves for a fixed epsilon (Alt & Godau, 1995),
+/// where cell `(i, j)` is the product of the `i`-th segment of the first curve
+/// and the `j`-th segment of the second.
+///
+/// Within a cell, the free space `{CurveDistFn <= epsilon}` is the
+/// intersection of an ellipse with the cell, so it is convex and determined by
+/// its intervals on the cell boundaries. Each of those is the part of a segment
+/// within distance epsilon of a vertex of the other curve.
+pub struct FreeSpaceDiagram {
+    n: usize,
+    m: usize,
+    epsilon: Dist,
+    /// Free intervals on the vertical boundary `x = i` of the cells in row
+    /// `j`, as parameters on the `j`-th segment of the second curve, indexed
+    /// `[i][j]`
+    vertical: Vec<Vec<Interval>>,
+    /// Free intervals on the horizontal boundary `y = j` of the cells in
+    /// column `i`, as parameters on the `i`-th segment of the first curve,
+    /// indexed `[i][j]`
+    horizontal: Vec<Vec<Interval>>,
+}
+
+impl FreeSpaceDiagram {
+    pub fn new<const D: usize>(curves: [&Curve<D>; 2], epsilon: Dist) -> Self {
+        let [points_1, points_2] = curves.map(|curve| curve.points());
+        let [segments_1, segments_2] = curves.map(segments);
+        let (n, m) = (segments_1.len(), segments_2.len());
+
+        let vertex_1 = |i: usize| &points_1[i.min(points_1.len() - 1)];
+        let vertex_2 = |j: usize| &points_2[j.min(points_2.len() - 1)];
+
+        let vertical = (0..=n)
+            .map(|i| {
+                segments_2
+                    .iter()
+                    .map(|segment| {
+                        segment.ball_intersection(vertex_1(i), epsilon)
+                    })
+                    .collect()
+            })
+            .collect();
+        let horizontal = segments_1
+            .iter()
+            .map(|segment| {
+                (0..=m)
+                    .map(|j| segment.ball_intersection(vertex_2(j), epsilon))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            n,
+            m,
+            epsilon,
+            vertical,
+            horizontal,
+        }
+    }
+
+    pub fn epsilon(&self) -> Dist {
+        self.epsilon
+    }
+
+    /// Returns the number of cells along the first and second curve, i.e.
+    /// their numbers of segments.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n, self.m)
+    }
+
+    /// Returns the free interval on the vertical boundary `x = i` of the cells
+    /// in row `j`.
+    pub fn vertical(&self, i: usize, j: usize) -> Interval {
+        self.vertical[i][j]
+    }
+
+    /// Returns the free interval on the horizontal boundary `y = j` of the
+    /// cells in column `i`.
+    pub fn horizontal(&self, i: usize, j: usize) -> Interval {
+        self.horizontal[i][j]
+    }
+
+    pub fn cell(&self, i: usize, j: usize) -> FreeCell {
+        FreeCell {
+            left: self.vertical[i][j],
+            right: self.vertical[i + 1][j],
+            bottom: self.horizontal[i][j],
+            top: self.horizontal[i][j + 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn parallel_segments() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 1.0],
+            point![1.0, 1.0],
+            point![2.0, 1.0],
+        ]);
+
+        // Leashes of length 1.25 reach 0.75 along the other curve
+        let diagram = FreeSpaceDiagram::new([&curve_1, &curve_2], 1.25);
+        assert_eq!(diagram.shape(), (1, 2));
+
+        // Points of the second curve close to (0, 0) and (2, 0)
+        let cell = diagram.cell(0, 0);
+        let [lo, hi] = cell.left.unwrap();
+        assert_relative_eq!(lo, 0.0);
+        assert_relative_eq!(hi, 0.75);
+        assert_eq!(cell.right, None);
+        let [lo, hi] = diagram.cell(0, 1).right.unwrap();
+        assert_relative_eq!(lo, 0.25);
+        assert_relative_eq!(hi, 1.0);
+
+        // Points of the first curve close to (1, 1)
+        let [lo, hi] = cell.top.unwrap();
+        assert_relative_eq!(lo, 0.125);
+        assert_relative_eq!(hi, 0.875);
+        assert_eq!(cell.top, diagram.cell(0, 1).bottom);
+
+        let diagram = FreeSpaceDiagram::new([&curve_1, &curve_2], 0.5);
+        assert_eq!(
+            diagram.cell(0, 0),
+            FreeCell {
+                left: None,
+                right: None,
+                bottom: None,
+                top: None,
+            }
+        );
+    }
+}
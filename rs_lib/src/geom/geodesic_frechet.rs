@@ -0,0 +1,68 @@
+use itertools::Itertools;
+
+use crate::geom::curve::{subdivide_lengths, Curve};
+use crate::geom::discrete_frechet::discrete_frechet_by;
+use crate::geom::polygonal_domain::PolygonalDomain;
+use crate::geom::Dist;
+use crate::math::function::Function;
+
+/// Approximates the Fréchet distance between the two curves where the leash
+/// length is the geodesic distance in `domain`, by the discrete Fréchet
+/// distance between points sampled at most `resolution` apart along both
+/// curves (including their vertices).
+pub fn geodesic_frechet(
+    curves: [&Curve; 2],
+    domain: &PolygonalDomain,
+    resolution: Dist,
+) -> Dist {
+    let [samples_1, samples_2] = curves.map(|curve| {
+        subdivide_lengths(
+            curve.cumulative_lengths(),
+            resolution,
+            [0., curve.total_length()],
+        )
+        .into_iter()
+        .map(|length| curve.eval(length))
+        .collect_vec()
+    });
+
+    discrete_frechet_by([&samples_1, &samples_2], |p, q| domain.dist(p, q)).dist
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn wall_between_curves() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+        let curve_2 =
+            Curve::from_points(vec![point![0.0, 2.0], point![1.0, 2.0]]);
+        let wall = vec![
+            point![-1.0, 0.9],
+            point![3.0, 0.9],
+            point![3.0, 1.1],
+            point![-1.0, 1.1],
+        ];
+
+        let unobstructed = PolygonalDomain::default();
+        assert_relative_eq!(
+            geodesic_frechet([&curve_1, &curve_2], &unobstructed, 0.1),
+            2.0,
+            epsilon = 1e-4
+        );
+
+        // At the end, the leash has to go around either end of the wall
+        let domain = PolygonalDomain::new(vec![wall]);
+        let expected = 2. * Dist::hypot(2.0, 0.9) + 0.2;
+        assert_relative_eq!(
+            geodesic_frechet([&curve_1, &curve_2], &domain, 0.1),
+            expected,
+            epsilon = 1e-3
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::iter;
+
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::segments;
+use crate::geom::Dist;
+
+#[derive(Serialize)]
+pub struct Hausdorff {
+    pub dist: Dist,
+    /// Pair of points realizing the distance, one on each curve
+    pub witness_points: [Point<Dist, 2>; 2],
+    /// Arc lengths of the witness points along their curves
+    pub witness_lengths: [Dist; 2],
+}
+
+/// Returns the roots in `[0, 1]` of `a*t^2 + b*t + c`.
+fn unit_roots([a, b, c]: [Dist; 3]) -> Vec<Dist> {
+    let roots = if a == 0. {
+        if b == 0. {
+            vec![]
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant < 0. {
+            vec![]
+        } else {
+            let sqrt_discriminant = discriminant.sqrt();
+            vec![
+                (-b - sqrt_discriminant) / (2. * a),
+                (-b + sqrt_discriminant) / (2. * a),
+            ]
+        }
+    };
+
+    roots
+        .into_iter()
+        .filter(|t| (0. ..=1.).contains(t))
+        .collect()
+}
+
+/// Computes the directed Hausdorff distance from `from` to `to`, i.e. the
+/// largest distance from a point on `from` to its closest point on `to`.
+///
+/// Along a segment of `from`, the distance to `to` is the lower envelope of
+/// the (convex) distances to the vertices of `to` and to the lines supporting
+/// its segments. Its maximum is therefore attained at an endpoint of the
+/// segment, or where the (squared) distances to two of these features are
+/// equal.
+pub fn hausdorff_directed(from: &Curve, to: &Curve) -> Hausdorff {
+    let segments_from = segments(from);
+    let segments_to = segments(to);
+
+    // Closest point on `to`, as (squared distance, segment index, parameter)
+    let closest_on_to = |point: &Point<Dist, 2>| {
+        segments_to
+            .iter()
+            .enumerate()
+            .map(|(segment_idx, segment)| {
+                let t = segment.closest_param(point);
+                let dist_squared = (segment.point_at(t) - point).norm_squared();
+                (dist_squared, segment_idx, t)
+            })
+            .min_by(|(dist_1, ..), (dist_2, ..)| dist_1.total_cmp(dist_2))
+            .unwrap()
+    };
+
+    segments_from
+        .iter()
+        .enumerate()
+        .flat_map(|(segment_idx, segment)| {
+            let quadratics = to
+                .points()
+                .iter()
+                .map(|point| segment.point_dist_squared_quadratic(point))
+                .chain(segments_to.iter().filter_map(|segment_to| {
+                    segment.line_dist_squared_quadratic(segment_to)
+                }))
+                .collect_vec();
+
+            let tie_params = quadratics
+                .iter()
+                .tuple_combinations::<(_, _)>()
+                .flat_map(|([a1, b1, c1], [a2, b2, c2])| {
+                    unit_roots([a1 - a2, b1 - b2, c1 - c2])
+                });
+
+            iter::once(0.)
+                .chain(iter::once(1.))
+                .chain(tie_params)
+                .map(move |t| (segment_idx, t, segment.point_at(t)))
+                .collect_vec()
+        })
+        .map(|(segment_idx_from, t_from, point_from)| {
+            let (dist_squared, segment_idx_to, t_to) =
+                closest_on_to(&point_from);
+            Hausdorff {
+                dist: dist_squared.sqrt(),
+                witness_points: [
+                    point_from,
+                    segments_to[segment_idx_to].point_at(t_to),
+                ],
+                witness_lengths: [
+                    from.segment_param_to_length(segment_idx_from, t_from),
+                    to.segment_param_to_length(segment_idx_to, t_to),
+                ],
+            }
+        })
+        .max_by(|h1, h2| h1.dist.total_cmp(&h2.dist))
+        .unwrap()
+}
+
+/// Computes the (symmetric) Hausdorff distance between the two curves. The
+/// witness is ordered like the curves, regardless of which direction attains
+/// the maximum.
+pub fn hausdorff(curve_1: &Curve, curve_2: &Curve) -> Hausdorff {
+    let forward = hausdorff_directed(curve_1, curve_2);
+    let mut backward = hausdorff_directed(curve_2, curve_1);
+
+    if forward.dist >= backward.dist {
+        forward
+    } else {
+        backward.witness_points.reverse();
+        backward.witness_lengths.reverse();
+        backward
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn directed_and_symmetric() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![4.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 1.0],
+            point![2.0, 3.0],
+            point![4.0, 1.0],
+        ]);
+
+        // The farthest point of the first curve lies in its middle, where the
+        // closest points on both segments of the second curve are equally far
+        let directed = hausdorff_directed(&curve_1, &curve_2);
+        assert_relative_eq!(
+            directed.dist,
+            3.0 / Dist::sqrt(2.0),
+            max_relative = 1e-5
+        );
+        assert_relative_eq!(
+            directed.witness_points[0],
+            point![2.0, 0.0],
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(directed.witness_lengths[0], 2.0, epsilon = 1e-5);
+
+        let symmetric = hausdorff(&curve_1, &curve_2);
+        assert_relative_eq!(symmetric.dist, 3.0, max_relative = 1e-5);
+        assert_relative_eq!(
+            symmetric.witness_points[0],
+            point![2.0, 0.0],
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            symmetric.witness_points[1],
+            point![2.0, 3.0],
+            epsilon = 1e-5
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use itertools::Itertools;
+use nalgebra::{point, Point};
+
+use crate::geom::curve::{subdivide_lengths, Curve};
+use crate::geom::curve_dist_fn::CurveDistFn;
+use crate::geom::Dist;
+use crate::math::function::Function;
+use crate::math::path_integral::PathIntegral;
+
+pub struct IntegralFrechet {
+    /// Integral of the leash length along `path`
+    pub integral: Dist,
+    /// Integral divided by the L1 length `L1 + L2` of any monotone path
+    pub average: Dist,
+    /// Monotone path in parameter space from `(0, 0)` to `(L1, L2)`
+    pub path: Vec<Point<Dist, 2>>,
+}
+
+/// Returns the integral of the leash length along the monotone `path` in
+/// parameter space, with respect to L1 arc length.
+pub fn path_cost(
+    curves: [&Curve; 2],
+    path: &[Point<Dist, 2>],
+    resolution: Dist,
+) -> Dist {
+    CurveDistFn::new(curves).path_integral(path, resolution)
+}
+
+/// Approximates the integral (or average) Fréchet distance, i.e. the smallest
+/// integral of the leash length over all monotone paths through parameter
+/// space, by a shortest path in a grid whose lines lie at most `resolution`
+/// apart and include all vertices of both curves.
+pub fn integral_frechet(
+    curves: [&Curve; 2],
+    resolution: Dist,
+) -> IntegralFrechet {
+    let curve_dist_fn = CurveDistFn::new(curves);
+
+    let [xs, ys] = curves.map(|curve| {
+        subdivide_lengths(
+            curve.cumulative_lengths(),
+            resolution,
+            [0., curve.total_length()],
+        )
+    });
+    let (n, m) = (xs.len(), ys.len());
+    let grid_point = |i: usize, j: usize| point![xs[i], ys[j]];
+
+    // Smallest integral over monotone grid paths from the origin to `(i, j)`,
+    // stored at index `i * m + j`, together with the predecessor on that path
+    let mut costs = vec![Dist::INFINITY; n * m];
+    let mut predecessors = vec![None; n * m];
+    costs[0] = 0.;
+
+    for (i, j) in Itertools::cartesian_product(0..n, 0..m) {
+        let target = grid_point(i, j);
+        let sources = [
+            (i > 0 && j > 0).then(|| (i - 1, j - 1)),
+            (i > 0).then(|| (i - 1, j)),
+            (j > 0).then(|| (i, j - 1)),
+        ];
+
+        for (i_prev, j_prev) in sources.into_iter().flatten() {
+            let cost = costs[i_prev * m + j_prev]
+                + curve_dist_fn.path_integral(
+                    &[grid_point(i_prev, j_prev), target],
+                    resolution,
+                );
+            if cost < costs[i * m + j] {
+                costs[i * m + j] = cost;
+                predecessors[i * m + j] = Some((i_prev, j_prev));
+            }
+        }
+    }
+
+    let mut path = vec![grid_point(n - 1, m - 1)];
+    let mut current = (n - 1, m - 1);
+    while let Some((i, j)) = predecessors[current.0 * m + current.1] {
+        path.push(grid_point(i, j));
+        current = (i, j);
+    }
+    path.reverse();
+
+    let integral = costs[n * m - 1];
+    let total_length = xs[n - 1] + ys[m - 1];
+    IntegralFrechet {
+        integral,
+        average: if total_length > 0. {
+            integral / total_length
+        } else {
+            curve_dist_fn.eval(point![0., 0.])
+        },
+        path,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn parallel_lines() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 =
+            Curve::from_points(vec![point![0.0, 1.0], point![2.0, 1.0]]);
+
+        // The diagonal keeps the leash at length 1 over an L1 length of 4
+        let result = integral_frechet([&curve_1, &curve_2], 0.25);
+        assert_relative_eq!(result.integral, 4.0, max_relative = 1e-4);
+        assert_relative_eq!(result.average, 1.0, max_relative = 1e-4);
+        assert_relative_eq!(
+            path_cost([&curve_1, &curve_2], &result.path, 0.25),
+            result.integral,
+            max_relative = 1e-4
+        );
+    }
+}
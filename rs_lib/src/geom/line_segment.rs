@@ -207,6 +207,130 @@ impl<T: RealField + Copy, const D: usize> LineSegment<T, D> {
     pub fn dist(&self, other: &Self) -> T {
         self.dist_squared(other).sqrt()
     }
+
+    /// Returns the interval of parameters `t` in `[0, 1]` for which the point
+    /// at `t` lies within distance `radius` of `center`, or `None` if there is
+    /// no such parameter.
+    pub fn ball_intersection(
+        &self,
+        center: &Point<T, D>,
+        radius: T,
+    ) -> Option<[T; 2]> {
+        let [p0, p1] = self.0;
+
+        let zero = T::zero();
+        let one = T::one();
+
+        let d = p1 - p0;
+        let w = p0 - center;
+
+        // Solve |w + t*d|^2 = radius^2, i.e. a*t^2 + 2*b*t + c = 0
+        let a = d.dot(&d);
+        let b = d.dot(&w);
+        let c = w.dot(&w) - radius * radius;
+
+        if a == zero {
+            // Degenerate segment: either all parameters are inside, or none
+            return if c <= zero { Some([zero, one]) } else { None };
+        }
+
+        let discriminant = b * b - a * c;
+        if discriminant < zero {
+            return None;
+        }
+
+        let sqrt_discriminant = discriminant.sqrt();
+        let lo = ((-b - sqrt_discriminant) / a).max(zero);
+        let hi = ((-b + sqrt_discriminant) / a).min(one);
+
+        if lo <= hi {
+            Some([lo, hi])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the parameter `t` in `[0, 1]` of the point on the segment that
+    /// is equidistant to `a` and `b`, or `None` if the bisector of `a` and `b`
+    /// does not cross the segment in a single point.
+    pub fn bisector_intersection(
+        &self,
+        a: &Point<T, D>,
+        b: &Point<T, D>,
+    ) -> Option<T> {
+        let [p0, p1] = self.0;
+
+        let normal = b - a;
+        let mid_point = a + normal * T::from_subset(&0.5);
+
+        let denominator = (p1 - p0).dot(&normal);
+        if denominator == T::zero() {
+            return None;
+        }
+
+        let t = (mid_point - p0).dot(&normal) / denominator;
+        if T::zero() <= t && t <= T::one() {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the point at parameter `t` in `[0, 1]` along the segment.
+    pub fn point_at(&self, t: T) -> Point<T, D> {
+        let [p0, p1] = self.0;
+        p0 + (p1 - p0) * t
+    }
+
+    /// Returns the parameter `t` in `[0, 1]` of the point on the segment
+    /// closest to `point`.
+    pub fn closest_param(&self, point: &Point<T, D>) -> T {
+        let [p0, p1] = self.0;
+
+        let d = p1 - p0;
+        let a = d.dot(&d);
+
+        if a == T::zero() {
+            T::zero()
+        } else {
+            ((point - p0).dot(&d) / a).clamp(T::zero(), T::one())
+        }
+    }
+
+    /// Returns the squared distance from `point` to the closest point on the
+    /// segment.
+    pub fn point_dist_squared(&self, point: &Point<T, D>) -> T {
+        (self.point_at(self.closest_param(point)) - point).norm_squared()
+    }
+
+    /// Returns the coefficients `[a, b, c]` such that the squared distance
+    /// from the point at parameter `t` to `point` equals `a*t^2 + b*t + c`.
+    pub fn point_dist_squared_quadratic(&self, point: &Point<T, D>) -> [T; 3] {
+        let [p0, p1] = self.0;
+
+        let d = p1 - p0;
+        let w = p0 - point;
+
+        [d.dot(&d), (d.dot(&w)) * T::from_subset(&2.), w.dot(&w)]
+    }
+
+    /// Returns the coefficients `[a, b, c]` such that the squared distance
+    /// from the point at parameter `t` to the line supporting `other` equals
+    /// `a*t^2 + b*t + c`, or `None` if `other` is degenerate.
+    pub fn line_dist_squared_quadratic(&self, other: &Self) -> Option<[T; 3]> {
+        let [p0, p1] = self.0;
+        let [q0, q1] = other.0;
+
+        let u = (q1 - q0).try_normalize(T::zero())?;
+
+        // Components perpendicular to the line
+        let d = p1 - p0;
+        let d = d - u * u.dot(&d);
+        let w = p0 - q0;
+        let w = w - u * u.dot(&w);
+
+        Some([d.dot(&d), (d.dot(&w)) * T::from_subset(&2.), w.dot(&w)])
+    }
 }
 
 impl<T: Scalar, const D: usize> From<(Point<T, D>, Point<T, D>)>
@@ -0,0 +1,352 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use itertools::Itertools;
+use nalgebra::Point;
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::{frechet_dist, propagate_row, segments, tolerance};
+use crate::geom::planar_graph::PlanarGraph;
+use crate::geom::Dist;
+
+/// How a reachable interval on a vertex of the graph was first reached.
+#[derive(Copy, Clone)]
+enum Source {
+    /// The path starts at the vertex
+    Start,
+    /// The path stays at the vertex while the curve passes one of its vertices
+    Stay,
+    /// The path arrives along the edge from the given vertex, where the given
+    /// segment of the curve was reachable
+    Edge(usize, usize),
+}
+
+#[derive(Copy, Clone)]
+struct Reachable {
+    interval: [Dist; 2],
+    source: Source,
+}
+
+/// Lowest reachable point on segment `i` of the curve for graph vertex `v`,
+/// ordered such that the event with the lowest position along the curve comes
+/// first.
+struct Event {
+    position: Dist,
+    v: usize,
+    i: usize,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Event {}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Dist::total_cmp(&other.position, &self.position)
+    }
+}
+
+/// Reachable intervals of the free-space surface of a curve and a planar
+/// graph, i.e. the points of the curve that can be matched to each graph
+/// vertex by a path in the graph whose Fréchet distance to the preceding part
+/// of the curve is at most epsilon.
+struct SurfaceReachability {
+    n: usize,
+    /// Reachable interval on segment `i` of the curve for vertex `v`, stored
+    /// at index `v * n + i`
+    reachable: Vec<Option<Reachable>>,
+}
+
+impl SurfaceReachability {
+    /// Propagates reachable intervals over the free-space diagrams of the curve
+    /// and the edges of the graph (Alt et al., 2003).
+    ///
+    /// Propagation never moves backwards along the curve, so like in
+    /// Dijkstra's algorithm, the interval on a vertex and segment is final
+    /// once it has the lowest position among those not yet processed. Each
+    /// of them is therefore propagated along the incident edges only once, in
+    /// order of their positions. Along each edge, the part of the row that was
+    /// entered from the left is not propagated through again, so each row is
+    /// traversed about once.
+    fn new(curve: &Curve, graph: &PlanarGraph, epsilon: Dist) -> Self {
+        let points = curve.points();
+        let segments = segments(curve);
+        let n = segments.len();
+        let vertices = graph.vertices();
+
+        let free = |v: usize, i: usize| {
+            segments[i].ball_intersection(&vertices[v], epsilon)
+        };
+
+        let mut reachable: Vec<Option<Reachable>> =
+            vec![None; vertices.len() * n];
+        let mut processed = vec![false; vertices.len() * n];
+        // End of the part of the row of each edge, indexed like the neighbors
+        // of its first vertex, that has been entered from the left
+        let mut row_ends = (0..vertices.len())
+            .map(|u| vec![0; graph.neighbors(u).len()])
+            .collect_vec();
+        let mut queue = BinaryHeap::new();
+
+        let update = |reachable: &mut Vec<Option<Reachable>>,
+                      queue: &mut BinaryHeap<Event>,
+                      (v, i): (usize, usize),
+                      interval: [Dist; 2],
+                      source: Source| {
+            let current = &mut reachable[v * n + i];
+            if current.is_none_or(|current| interval[0] < current.interval[0]) {
+                *current = Some(Reachable { interval, source });
+                queue.push(Event {
+                    position: i as Dist + interval[0],
+                    v,
+                    i,
+                });
+            }
+        };
+
+        for v in 0..vertices.len() {
+            if let Some(interval) = free(v, 0).filter(|&[lo, _]| lo <= 0.) {
+                update(
+                    &mut reachable,
+                    &mut queue,
+                    (v, 0),
+                    interval,
+                    Source::Start,
+                );
+            }
+        }
+
+        while let Some(Event { v: u, i: j, .. }) = queue.pop() {
+            if std::mem::replace(&mut processed[u * n + j], true) {
+                continue;
+            }
+            let Reachable { interval, .. } = reachable[u * n + j].unwrap();
+
+            let [_, hi] = interval;
+            if hi >= 1. && j + 1 < n {
+                if let Some(next) = free(u, j + 1).filter(|&[lo, _]| lo <= 0.) {
+                    update(
+                        &mut reachable,
+                        &mut queue,
+                        (u, j + 1),
+                        next,
+                        Source::Stay,
+                    );
+                }
+            }
+
+            for (&w, row_end) in graph.neighbors(u).iter().zip(&mut row_ends[u])
+            {
+                if j < *row_end {
+                    continue;
+                }
+
+                *row_end = propagate_row(
+                    points,
+                    &segments,
+                    [&vertices[u], &vertices[w]],
+                    epsilon,
+                    (j, interval),
+                    |i, top| {
+                        if !processed[w * n + i] {
+                            update(
+                                &mut reachable,
+                                &mut queue,
+                                (w, i),
+                                top,
+                                Source::Edge(u, j),
+                            );
+                        }
+                    },
+                );
+            }
+        }
+
+        Self { n, reachable }
+    }
+
+    /// Returns the vertices of a path in the graph that is matched to the
+    /// entire curve, if any.
+    fn path(&self) -> Option<Vec<usize>> {
+        let n = self.n;
+        let num_vertices = self.reachable.len() / n;
+
+        let mut v = (0..num_vertices).find(|&v| {
+            matches!(self.reachable[v * n + n - 1], Some(Reachable { interval: [_, hi], .. }) if hi >= 1.)
+        })?;
+        let mut i = n - 1;
+
+        let mut path = vec![v];
+        loop {
+            match self.reachable[v * n + i].unwrap().source {
+                Source::Start => break,
+                Source::Stay => i -= 1,
+                Source::Edge(u, j) => {
+                    path.push(u);
+                    (v, i) = (u, j);
+                }
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Returns the vertices of a path in the graph whose Fréchet distance to the
+/// curve is at most `epsilon`, or `None` if there is no such path. Paths start
+/// and end at vertices of the graph.
+pub fn map_match_decide(
+    curve: &Curve,
+    graph: &PlanarGraph,
+    epsilon: Dist,
+) -> Option<Vec<usize>> {
+    SurfaceReachability::new(curve, graph, epsilon).path()
+}
+
+#[derive(Serialize)]
+pub struct MapMatch {
+    /// Fréchet distance between the curve and the matched path
+    pub dist: Dist,
+    /// Indices of the graph vertices along the matched path
+    pub path: Vec<usize>,
+}
+
+/// Finds the path in the graph with (approximately) the smallest Fréchet
+/// distance to the curve, by bisecting over epsilon with
+/// [`map_match_decide`]. Returns `None` if the graph has no vertices.
+pub fn map_match(curve: &Curve, graph: &PlanarGraph) -> Option<MapMatch> {
+    let vertices = graph.vertices();
+    let points = curve.points();
+
+    let closest_vertex_dist = |point: &Point<Dist, 2>| {
+        vertices
+            .iter()
+            .map(|vertex| (vertex - point).norm())
+            .fold(Dist::INFINITY, Dist::min)
+    };
+
+    // The start and end of the curve have to be matched to vertices, and any
+    // single vertex is a path
+    let mut lo = Dist::max(
+        closest_vertex_dist(points.first()?),
+        closest_vertex_dist(points.last()?),
+    );
+    let mut hi = vertices
+        .iter()
+        .map(|vertex| {
+            points
+                .iter()
+                .map(|point| (vertex - point).norm())
+                .fold(0., Dist::max)
+        })
+        .fold(Dist::INFINITY, Dist::min);
+
+    while hi - lo > hi * tolerance() {
+        let mid = (lo + hi) / 2.;
+        if map_match_decide(curve, graph, mid).is_some() {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    let path = map_match_decide(curve, graph, hi * (1. + tolerance()))
+        .expect("the upper bound should admit a matched path");
+    let dist = frechet_dist([curve, &graph.path_curve(&path)]);
+
+    Some(MapMatch { dist, path })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn grid_graph() {
+        // 3 x 2 grid:
+        //   3 - 4 - 5
+        //   |   |   |
+        //   0 - 1 - 2
+        let graph = PlanarGraph::new(
+            vec![
+                point![0.0, 0.0],
+                point![1.0, 0.0],
+                point![2.0, 0.0],
+                point![0.0, 1.0],
+                point![1.0, 1.0],
+                point![2.0, 1.0],
+            ],
+            vec![[0, 1], [1, 2], [3, 4], [4, 5], [0, 3], [1, 4], [2, 5]],
+        );
+        let curve = Curve::from_points(vec![
+            point![0.0, 0.1],
+            point![1.1, 0.1],
+            point![1.1, 0.9],
+            point![2.0, 0.9],
+        ]);
+
+        let result = map_match(&curve, &graph).unwrap();
+        assert_eq!(result.path, vec![0, 1, 4, 5]);
+        assert_relative_eq!(
+            result.dist,
+            Dist::hypot(0.1, 0.1),
+            max_relative = 1e-3
+        );
+
+        assert!(map_match_decide(&curve, &graph, 0.1).is_none());
+    }
+
+    #[test]
+    fn long_trace() {
+        // Staircase through a 10 x 10 grid, traced by a noisy curve with many
+        // points per edge
+        let size = 10;
+        let vertices = Itertools::cartesian_product(0..size, 0..size)
+            .map(|(y, x)| point![x as Dist, y as Dist])
+            .collect();
+        let edges = Itertools::cartesian_product(0..size, 0..size)
+            .flat_map(|(y, x)| {
+                let idx = y * size + x;
+                [
+                    (x + 1 < size).then_some([idx, idx + 1]),
+                    (y + 1 < size).then_some([idx, idx + size]),
+                ]
+            })
+            .flatten()
+            .collect();
+        let graph = PlanarGraph::new(vertices, edges);
+
+        let points = (0..(size - 1) * 2 * 5 + 1)
+            .map(|step| {
+                let (edge, t) = (step / 5, (step % 5) as Dist / 5.);
+                let corner = (edge / 2) as Dist;
+                let noise = if step % 2 == 0 { 0.05 } else { -0.05 };
+                match edge % 2 {
+                    0 => point![corner + t, corner + noise],
+                    _ => point![corner + 1. + noise, corner + t],
+                }
+            })
+            .collect();
+        let curve = Curve::from_points(points);
+
+        let result = map_match(&curve, &graph).unwrap();
+        assert_eq!(result.path.len(), (size - 1) * 2 + 1);
+        assert_eq!(result.path.first(), Some(&0));
+        assert_eq!(result.path.last(), Some(&(size * size - 1)));
+        assert!(result.dist <= 0.05 * (1. + 1e-3));
+    }
+}
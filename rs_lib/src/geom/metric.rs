@@ -0,0 +1,398 @@
+use itertools::Itertools;
+use nalgebra::{Point, SVector};
+use serde::Deserialize;
+
+use crate::geom::line_segment::LineSegment;
+use crate::geom::segment_bvh::extreme_points;
+use crate::geom::Dist;
+
+/// Number of golden-section iterations used to minimize the distance from a
+/// point to a segment, which shrinks the search interval by a factor of about
+/// 10^-13.
+const GOLDEN_SECTION_ITERATIONS: usize = 64;
+
+/// Returns the smallest value of a convex function on `[0, 1]`, found by a
+/// golden-section search.
+fn golden_section_min(f: impl Fn(Dist) -> Dist) -> Dist {
+    let ratio = (Dist::sqrt(5.) - 1.) / 2.;
+
+    let [mut lo, mut hi] = [0., 1.];
+    for _ in 0..GOLDEN_SECTION_ITERATIONS {
+        let t1 = hi - ratio * (hi - lo);
+        let t2 = lo + ratio * (hi - lo);
+        if f(t1) < f(t2) {
+            hi = t2;
+        } else {
+            lo = t1;
+        }
+    }
+
+    f(0.).min(f(1.)).min(f((lo + hi) / 2.))
+}
+
+/// Distance between points in `D` dimensions, in which leash lengths are
+/// measured.
+///
+/// The provided methods assume that the metric is induced by a norm, i.e. that
+/// it is translation invariant and convex along segments. Metrics for which
+/// this does not hold should override them.
+pub trait Metric<const D: usize = 2> {
+    fn dist(&self, p: &Point<Dist, D>, q: &Point<Dist, D>) -> Dist;
+
+    /// Returns the gradient of the distance with respect to `p`, which is zero
+    /// where `p` and `q` coincide.
+    ///
+    /// By default, it is approximated by central differences. Rounding `p`
+    /// costs an absolute error of about `Dist::EPSILON * |p|`, while the
+    /// truncation error grows with the spacing relative to `|p - q|`, so the
+    /// spacing balances both with a cube root. Far from the origin it stays
+    /// well above the precision of `Dist`, and it never spans `q`.
+    fn dist_gradient(
+        &self,
+        p: &Point<Dist, D>,
+        q: &Point<Dist, D>,
+    ) -> SVector<Dist, D> {
+        let leash = (p - q).norm();
+        if leash == 0. {
+            return SVector::zeros();
+        }
+        let spacing = Dist::EPSILON.cbrt()
+            * (p.coords.norm().max(leash) * leash.powi(2)).cbrt();
+
+        SVector::from_fn(|idx, _| {
+            let mut offset = SVector::zeros();
+            offset[idx] = spacing;
+            (self.dist(&(p + offset), q) - self.dist(&(p - offset), q))
+                / (2. * spacing)
+        })
+    }
+
+    /// Returns the smallest distance from `point` to a point on `segment`.
+    ///
+    /// The distance is convex along the segment, so it is minimized by a
+    /// golden-section search.
+    fn point_segment_dist(
+        &self,
+        point: &Point<Dist, D>,
+        segment: &LineSegment<Dist, D>,
+    ) -> Dist {
+        golden_section_min(|t| self.dist(point, &segment.point_at(t)))
+    }
+
+    /// Returns the smallest distance between points in two axis-aligned boxes,
+    /// each given by its minimum and maximum corners.
+    ///
+    /// This is the distance across the gaps between the boxes along each axis,
+    /// assuming that the distance grows with the absolute coordinate
+    /// differences, as it does for all Lp norms and their weighted variants.
+    fn box_dist(
+        &self,
+        [min_1, max_1]: &[Point<Dist, D>; 2],
+        [min_2, max_2]: &[Point<Dist, D>; 2],
+    ) -> Dist {
+        let gap = (min_2 - max_1).sup(&(min_1 - max_2)).sup(&SVector::zeros());
+        self.dist(&Point::origin(), &Point::from(gap))
+    }
+
+    /// Returns the smallest distance between points on the two segments.
+    ///
+    /// The distance from a point on `l1` to `l2` is convex along `l1`, so it
+    /// is minimized by a golden-section search over
+    /// [`Self::point_segment_dist`].
+    fn segment_dist(
+        &self,
+        l1: &LineSegment<Dist, D>,
+        l2: &LineSegment<Dist, D>,
+    ) -> Dist {
+        golden_section_min(|t| self.point_segment_dist(&l1.point_at(t), l2))
+    }
+
+    /// Returns the largest distance between points in the convex hulls of
+    /// `points_1` and `points_2`. Since the distance is convex, it is attained
+    /// between extreme points of the hulls.
+    fn max_dist(
+        &self,
+        points_1: &[Point<Dist, D>],
+        points_2: &[Point<Dist, D>],
+    ) -> Dist {
+        let [points_1, points_2] = [points_1, points_2].map(extreme_points);
+        Itertools::cartesian_product(points_1.iter(), points_2.iter())
+            .map(|(p1, p2)| self.dist(p1, p2))
+            .fold(Dist::NEG_INFINITY, Dist::max)
+    }
+}
+
+/// Point metrics that can be selected from JavaScript.
+#[derive(Clone, Default, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PointMetric {
+    #[default]
+    Euclidean,
+    /// L1 distance, e.g. for paths on a grid
+    Manhattan,
+    /// L∞ distance
+    Chebyshev,
+    /// Euclidean distance after scaling the axes by the square roots of the
+    /// `weights`, i.e. `sqrt(w_1 * d_1^2 + ... + w_D * d_D^2)`. Missing weights
+    /// are 1.
+    Weighted { weights: Vec<Dist> },
+}
+
+impl PointMetric {
+    fn weights<const D: usize>(weights: &[Dist]) -> SVector<Dist, D> {
+        SVector::from_fn(|idx, _| weights.get(idx).copied().unwrap_or(1.))
+    }
+
+    fn scale<const D: usize>(
+        weights: &[Dist],
+        point: &Point<Dist, D>,
+    ) -> Point<Dist, D> {
+        let factors = Self::weights::<D>(weights).map(Dist::sqrt);
+        Point::from(point.coords.component_mul(&factors))
+    }
+
+    fn scale_segment<const D: usize>(
+        weights: &[Dist],
+        segment: &LineSegment<Dist, D>,
+    ) -> LineSegment<Dist, D> {
+        LineSegment::from((
+            Self::scale(weights, &segment.point_at(0.)),
+            Self::scale(weights, &segment.point_at(1.)),
+        ))
+    }
+}
+
+impl<const D: usize> Metric<D> for PointMetric {
+    fn dist(&self, p: &Point<Dist, D>, q: &Point<Dist, D>) -> Dist {
+        let diff = p - q;
+        match self {
+            Self::Euclidean => diff.norm(),
+            Self::Manhattan => diff.lp_norm(1),
+            Self::Chebyshev => diff.amax(),
+            Self::Weighted { weights } => diff
+                .component_mul(&diff)
+                .dot(&Self::weights(weights))
+                .sqrt(),
+        }
+    }
+
+    fn dist_gradient(
+        &self,
+        p: &Point<Dist, D>,
+        q: &Point<Dist, D>,
+    ) -> SVector<Dist, D> {
+        let diff = p - q;
+        let sign = |d: Dist| if d == 0. { 0. } else { d.signum() };
+        match self {
+            Self::Euclidean => {
+                diff.try_normalize(0.).unwrap_or_else(SVector::zeros)
+            }
+            Self::Manhattan => diff.map(sign),
+            // Only the largest coordinate difference counts, where ties go to
+            // the first axis
+            Self::Chebyshev => {
+                let mut gradient = SVector::zeros();
+                if D > 0 {
+                    let idx = diff.iamax();
+                    gradient[idx] = sign(diff[idx]);
+                }
+                gradient
+            }
+            Self::Weighted { weights } => {
+                let dist = self.dist(p, q);
+                if dist > 0. {
+                    diff.component_mul(&Self::weights(weights)) / dist
+                } else {
+                    SVector::zeros()
+                }
+            }
+        }
+    }
+
+    fn point_segment_dist(
+        &self,
+        point: &Point<Dist, D>,
+        segment: &LineSegment<Dist, D>,
+    ) -> Dist {
+        match self {
+            Self::Euclidean => segment.point_dist_squared(point).sqrt(),
+            Self::Weighted { weights } => Self::scale_segment(weights, segment)
+                .point_dist_squared(&Self::scale(weights, point))
+                .sqrt(),
+            Self::Manhattan | Self::Chebyshev => {
+                let [start, end] = [segment.point_at(0.), segment.point_at(1.)];
+                // The distance is piecewise linear along the segment, with
+                // breakpoints where a coordinate difference vanishes (L1), or
+                // where additionally two of them are equal in absolute value
+                // (L∞), so it is minimized at one of those or at an endpoint
+                let dir = end - start;
+                let offset = point - start;
+                let zeros = (0..D).map(|i| offset[i] / dir[i]);
+                let ties = (0..D).tuple_combinations().flat_map(|(i, j)| {
+                    [
+                        (offset[i] + offset[j]) / (dir[i] + dir[j]),
+                        (offset[i] - offset[j]) / (dir[i] - dir[j]),
+                    ]
+                });
+                let breakpoints = match self {
+                    Self::Manhattan => zeros.collect_vec(),
+                    _ => zeros.chain(ties).collect_vec(),
+                };
+
+                breakpoints
+                    .into_iter()
+                    .filter(|t| t.is_finite())
+                    .map(|t| t.clamp(0., 1.))
+                    .chain([0., 1.])
+                    .map(|t| self.dist(point, &segment.point_at(t)))
+                    .fold(Dist::INFINITY, Dist::min)
+            }
+        }
+    }
+
+    fn segment_dist(
+        &self,
+        l1: &LineSegment<Dist, D>,
+        l2: &LineSegment<Dist, D>,
+    ) -> Dist {
+        match self {
+            Self::Euclidean => l1.dist(l2),
+            Self::Weighted { weights } => Self::scale_segment(weights, l1)
+                .dist(&Self::scale_segment(weights, l2)),
+            Self::Manhattan | Self::Chebyshev => golden_section_min(|t| {
+                self.point_segment_dist(&l1.point_at(t), l2)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    /// Metric that only implements `dist`, to check the provided methods
+    struct Manhattan;
+
+    impl Metric for Manhattan {
+        fn dist(&self, p: &Point<Dist, 2>, q: &Point<Dist, 2>) -> Dist {
+            (p - q).lp_norm(1)
+        }
+    }
+
+    #[test]
+    fn gradient_far_from_origin() {
+        /// Euclidean distance without the analytic gradient
+        struct Numerical;
+
+        impl Metric for Numerical {
+            fn dist(&self, p: &Point<Dist, 2>, q: &Point<Dist, 2>) -> Dist {
+                (p - q).norm()
+            }
+        }
+
+        // A leash of about one unit, where the spacing of adjacent values of
+        // `Dist` is far from negligible
+        let p = point![1000.0, 1000.0];
+        let q = point![1001.0, 999.5];
+        assert_relative_eq!(
+            Manhattan.dist_gradient(&p, &q),
+            vector![-1.0, 1.0],
+            epsilon = 1e-3
+        );
+        assert_relative_eq!(
+            Numerical.dist_gradient(&p, &q),
+            (p - q).normalize(),
+            epsilon = 1e-2
+        );
+        assert_eq!(Manhattan.dist_gradient(&p, &p), vector![0.0, 0.0]);
+    }
+
+    #[test]
+    fn segment_dists() {
+        let l1 = LineSegment::from((point![0.0, 0.0], point![2.0, 0.0]));
+        let l2 = LineSegment::from((point![3.0, 1.0], point![4.0, 3.0]));
+        let l3 = LineSegment::from((point![1.0, -1.0], point![1.0, 1.0]));
+
+        let p = point![3.0, 1.0];
+        let q = point![2.0, 0.0];
+        assert_relative_eq!(
+            PointMetric::Euclidean.dist(&p, &q),
+            Dist::sqrt(2.)
+        );
+        assert_relative_eq!(PointMetric::Manhattan.dist(&p, &q), 2.0);
+        assert_relative_eq!(PointMetric::Chebyshev.dist(&p, &q), 1.0);
+        let weighted = PointMetric::Weighted {
+            weights: vec![4.0, 1.0],
+        };
+        assert_relative_eq!(weighted.dist(&p, &q), Dist::sqrt(5.));
+
+        for (metric, expected) in [
+            (PointMetric::Euclidean, Dist::sqrt(2.)),
+            (PointMetric::Manhattan, 2.0),
+            (PointMetric::Chebyshev, 1.0),
+            (weighted.clone(), Dist::sqrt(5.)),
+        ] {
+            assert_relative_eq!(
+                metric.segment_dist(&l1, &l2),
+                expected,
+                epsilon = 1e-5
+            );
+            assert_relative_eq!(
+                metric.segment_dist(&l1, &l3),
+                0.0,
+                epsilon = 1e-5
+            );
+        }
+
+        // Closest in L∞ strictly between the endpoints
+        let diagonal = LineSegment::from((point![0.0, 0.0], point![4.0, 4.0]));
+        assert_relative_eq!(
+            PointMetric::Chebyshev
+                .point_segment_dist(&point![3.0, 1.0], &diagonal),
+            1.0
+        );
+
+        assert_relative_eq!(
+            Manhattan.segment_dist(&l1, &l2),
+            2.0,
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            Manhattan.point_segment_dist(&point![1.0, 2.0], &l1),
+            2.0,
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    fn skew_segments() {
+        // Closest between the interiors of both segments, one unit apart
+        let l1 =
+            LineSegment::from((point![-1.0, 0.0, 0.0], point![1.0, 0.0, 0.0]));
+        let l2 =
+            LineSegment::from((point![0.0, -1.0, 1.0], point![0.0, 1.0, 1.0]));
+
+        for metric in [
+            PointMetric::Euclidean,
+            PointMetric::Manhattan,
+            PointMetric::Chebyshev,
+        ] {
+            assert_relative_eq!(
+                metric.segment_dist(&l1, &l2),
+                1.0,
+                epsilon = 1e-5
+            );
+        }
+        let weighted = PointMetric::Weighted {
+            weights: vec![1.0, 1.0, 4.0],
+        };
+        assert_relative_eq!(weighted.segment_dist(&l1, &l2), 2.0);
+        assert_relative_eq!(
+            weighted.dist_gradient(&point![0.0, 0.0, 1.0], &Point::origin()),
+            SVector::from([0.0, 0.0, 2.0])
+        );
+    }
+}
@@ -1,21 +1,173 @@
-use nalgebra::Point;
+use itertools::Itertools;
+use nalgebra::{Point, Vector2};
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
 
-use crate::math::function::Function;
-
-use self::curve::Curve;
+use self::any_curve::{with_curve_pair, AnyCurve, MAX_DIMENSION};
+use self::closed_frechet::closed_frechet;
+use self::clustering::{k_center, k_medians, CurveDistance};
+use self::curve::{Curve, Parameterization};
+use self::curve_index::CurveIndex;
+use self::discrete_frechet::discrete_frechet;
+use self::dtw::{dtw, DtwOptions};
+use self::edit_distance::{edr, erp, lcss};
+use self::frechet::{frechet_dist, partial_frechet, Interval};
+use self::frechet_mean::{frechet_mean, MeanObjective};
+use self::free_space::{FreeCell, FreeSpaceDiagram};
+use self::geodesic_frechet::geodesic_frechet;
+use self::hausdorff::hausdorff;
+use self::integral_frechet::{integral_frechet, path_cost};
+use self::map_matching::map_match;
+use self::planar_graph::PlanarGraph;
+use self::polygonal_domain::PolygonalDomain;
+use self::similarity::{
+    similarity_alignment, SimilarityCost, SimilarityTransform,
+};
+use self::translation_frechet::translation_frechet;
+use self::weak_frechet::weak_frechet_dist;
 
+pub mod any_curve;
+pub mod closed_frechet;
+pub mod clustering;
 pub mod curve;
 pub mod curve_dist_fn;
+pub mod curve_index;
+pub mod discrete_frechet;
+pub mod dtw;
+pub mod edit_distance;
+pub mod frechet;
+pub mod frechet_mean;
+pub mod free_space;
+pub mod geodesic_frechet;
+pub mod hausdorff;
+pub mod integral_frechet;
 pub mod line_segment;
+pub mod map_matching;
+pub mod metric;
+pub mod planar_graph;
+pub mod polygonal_domain;
+pub mod segment_bvh;
+pub mod similarity;
+pub mod translation_frechet;
+pub mod weak_frechet;
 
+/// Scalar type of the geometry and the distance fields, which is `f64` with
+/// the `f64` feature and `f32` otherwise.
+#[cfg(not(feature = "f64"))]
 pub type Dist = f32;
+#[cfg(feature = "f64")]
+pub type Dist = f64;
 
 #[wasm_bindgen(typescript_custom_section)]
 const TYPESCRIPT_CUSTOM_SECTION: &'static str = r#"
 export type IPoint = [x: number, y: number];
 export type IPoints = IPoint[];
+export type IPointNd = number[];
+export type IPointsNd = IPointNd[];
 export type ILengths = number[];
+export type IParameterization =
+    | { kind: 'arc_length' }
+    | { kind: 'vertex_index' }
+    | { kind: 'normalized' }
+    | { kind: 'timestamps'; timestamps: number[] };
+export type IPolygons = IPoints[];
+export type ICurvesPoints = IPoints[];
+export type IPointMetric =
+    | { kind: 'euclidean' }
+    | { kind: 'manhattan' }
+    | { kind: 'chebyshev' }
+    | { kind: 'weighted'; weights: number[] };
+export type IMeanObjective = 'center' | 'median';
+export type IFrechetMean = {
+    points: IPoints;
+    cost: number;
+};
+export type ICurveDistance =
+    | 'discrete_frechet'
+    | 'frechet'
+    | 'dtw'
+    | 'hausdorff';
+export type IClustering = {
+    centers: number[];
+    assignments: number[];
+    cost: number;
+};
+export type ISearchStats = {
+    num_curves: number;
+    pruned_by_endpoints: number;
+    pruned_by_bounding_box: number;
+    num_upper_bounds: number;
+    pruned_by_decision: number;
+    num_exact: number;
+};
+export type INearestNeighbor = {
+    idx: number;
+    dist: number;
+    stats: ISearchStats;
+};
+export type IEdges = [u: number, v: number][];
+export type IMapMatch = {
+    dist: number;
+    path: number[];
+};
+export type IDiscreteFrechet = {
+    dist: number;
+    coupling: [x: number, y: number][];
+};
+export type IDtwOptions = {
+    band_radius?: number;
+    itakura_slope?: number;
+};
+export type IDtw = {
+    cost: number;
+    path: [x: number, y: number][];
+};
+export type IAlignment = {
+    score: number;
+    pairs: [i: number, j: number][];
+};
+export type IHausdorff = {
+    dist: number;
+    witness_points: [IPoint, IPoint];
+    witness_params: [number, number];
+};
+export type IIntegralFrechet = {
+    integral: number;
+    average: number;
+    path: IPoints;
+};
+export type ITranslationFrechet = {
+    dist: number;
+    translation: IPoint;
+};
+export type ISimilarityCost = 'frechet' | 'dtw';
+export type ISimilarityTransform = {
+    rotation: number;
+    scale: number;
+    translation: IPoint;
+};
+export type ISimilarityAlignment = {
+    dist: number;
+    transform: ISimilarityTransform;
+};
+export type IClosedFrechet = {
+    dist: number;
+    lower_bound: number;
+    start_param: number;
+    matching: IPoints;
+};
+export type IFreeInterval = [lo: number, hi: number] | undefined;
+export type IFreeCell = {
+    left: IFreeInterval;
+    right: IFreeInterval;
+    bottom: IFreeInterval;
+    top: IFreeInterval;
+};
+export type IPartialFrechet = {
+    dist: number;
+    start_param: number;
+    end_param: number;
+};
 "#;
 
 #[wasm_bindgen]
@@ -24,8 +176,127 @@ extern "C" {
     pub type IPoint;
     #[wasm_bindgen(typescript_type = "IPoints")]
     pub type IPoints;
+    #[wasm_bindgen(typescript_type = "IPointsNd")]
+    pub type IPointsNd;
     #[wasm_bindgen(typescript_type = "ILengths")]
     pub type ILengths;
+    #[wasm_bindgen(typescript_type = "IParameterization")]
+    pub type IParameterization;
+    #[wasm_bindgen(typescript_type = "IPolygons")]
+    pub type IPolygons;
+    #[wasm_bindgen(typescript_type = "ICurvesPoints")]
+    pub type ICurvesPoints;
+    #[wasm_bindgen(typescript_type = "IPointMetric")]
+    pub type IPointMetric;
+    #[wasm_bindgen(typescript_type = "IMeanObjective")]
+    pub type IMeanObjective;
+    #[wasm_bindgen(typescript_type = "IFrechetMean")]
+    pub type IFrechetMean;
+    #[wasm_bindgen(typescript_type = "ICurveDistance")]
+    pub type ICurveDistance;
+    #[wasm_bindgen(typescript_type = "IClustering")]
+    pub type IClustering;
+    #[wasm_bindgen(typescript_type = "INearestNeighbor")]
+    pub type INearestNeighbor;
+    #[wasm_bindgen(typescript_type = "IEdges")]
+    pub type IEdges;
+    #[wasm_bindgen(typescript_type = "IMapMatch")]
+    pub type IMapMatch;
+    #[wasm_bindgen(typescript_type = "IDiscreteFrechet")]
+    pub type IDiscreteFrechet;
+    #[wasm_bindgen(typescript_type = "IDtwOptions")]
+    pub type IDtwOptions;
+    #[wasm_bindgen(typescript_type = "IDtw")]
+    pub type IDtw;
+    #[wasm_bindgen(typescript_type = "IAlignment")]
+    pub type IAlignment;
+    #[wasm_bindgen(typescript_type = "IHausdorff")]
+    pub type IHausdorff;
+    #[wasm_bindgen(typescript_type = "IIntegralFrechet")]
+    pub type IIntegralFrechet;
+    #[wasm_bindgen(typescript_type = "ITranslationFrechet")]
+    pub type ITranslationFrechet;
+    #[wasm_bindgen(typescript_type = "ISimilarityCost")]
+    pub type ISimilarityCost;
+    #[wasm_bindgen(typescript_type = "ISimilarityTransform")]
+    pub type ISimilarityTransform;
+    #[wasm_bindgen(typescript_type = "ISimilarityAlignment")]
+    pub type ISimilarityAlignment;
+    #[wasm_bindgen(typescript_type = "IClosedFrechet")]
+    pub type IClosedFrechet;
+    #[wasm_bindgen(typescript_type = "IPartialFrechet")]
+    pub type IPartialFrechet;
+    #[wasm_bindgen(typescript_type = "IFreeCell")]
+    pub type IFreeCell;
+}
+
+#[derive(Serialize)]
+struct JsDiscreteFrechet {
+    dist: Dist,
+    /// Coupling as pairs of parameters at the coupled points, i.e. points in
+    /// parameter space
+    coupling: Vec<[Dist; 2]>,
+}
+
+#[derive(Serialize)]
+struct JsDtw {
+    cost: Dist,
+    /// Warping path as pairs of parameters at the matched points, i.e. points
+    /// in parameter space
+    path: Vec<[Dist; 2]>,
+}
+
+#[derive(Serialize)]
+struct JsHausdorff {
+    dist: Dist,
+    witness_points: [Point<Dist, 2>; 2],
+    /// Parameters of the witness points on their curves
+    witness_params: [Dist; 2],
+}
+
+#[derive(Serialize)]
+struct JsClosedFrechet {
+    dist: Dist,
+    lower_bound: Dist,
+    /// Parameter on the second curve where the optimal matching starts
+    start_param: Dist,
+    matching: Vec<Point<Dist, 2>>,
+}
+
+#[derive(Serialize)]
+struct JsPartialFrechet {
+    dist: Dist,
+    /// Parameters on the second curve where the matched subcurve starts and
+    /// ends
+    start_param: Dist,
+    end_param: Dist,
+}
+
+#[derive(Serialize)]
+struct JsSimilarityAlignment {
+    dist: Dist,
+    transform: SimilarityTransform,
+}
+
+#[derive(Serialize)]
+struct JsFrechetMean<'a> {
+    points: &'a Vec<Point<Dist, 2>>,
+    cost: Dist,
+}
+
+#[derive(Serialize)]
+struct JsIntegralFrechet {
+    integral: Dist,
+    average: Dist,
+    path: Vec<Point<Dist, 2>>,
+}
+
+#[derive(Serialize)]
+struct JsFreeCell {
+    left: Option<[Dist; 2]>,
+    right: Option<[Dist; 2]>,
+    bottom: Option<[Dist; 2]>,
+    top: Option<[Dist; 2]>,
 }
 
 #[wasm_bindgen]
@@ -41,24 +312,75 @@ impl JsCurve {
         Self(Curve::from_points(points))
     }
 
-    pub fn with_point(&self, point: IPoint) -> Self {
+    /// Returns the curve with `point` appended. Curves parameterized by
+    /// timestamps also need the `timestamp` of the new point, which must not
+    /// precede the last one.
+    pub fn with_point(
+        &self,
+        point: IPoint,
+        timestamp: Option<Dist>,
+    ) -> Result<JsCurve, JsValue> {
         let point = serde_wasm_bindgen::from_value(point.into()).unwrap();
 
         let mut new_self = self.clone();
-        new_self.0.push(point);
         new_self
+            .0
+            .push(point, timestamp)
+            .map_err(|err| JsValue::from_str(&err))?;
+        Ok(new_self)
     }
 
     pub fn with_replaced_point(&self, point_idx: usize, point: IPoint) -> Self {
         let point = serde_wasm_bindgen::from_value(point.into()).unwrap();
 
-        let mut new_points = self.0.points().clone();
-        new_points[point_idx] = point;
-        Self(Curve::from_points(new_points))
+        let mut new_vertices = self.0.vertices().to_vec();
+        new_vertices[point_idx] = point;
+        Self(self.0.with_vertices(new_vertices))
+    }
+
+    pub fn with_closed(&self, closed: bool) -> Self {
+        Self(self.0.with_closed(closed))
+    }
+
+    /// Returns the curve traversed twice, spanning the doubled parameter
+    /// domain of a closed curve.
+    pub fn doubled(&self) -> Self {
+        Self(self.0.doubled())
+    }
+
+    pub fn translated(&self, translation: IPoint) -> Self {
+        let translation: Vector2<Dist> =
+            serde_wasm_bindgen::from_value(translation.into()).unwrap();
+        Self(self.0.translated(&translation))
     }
 
-    pub fn at(&self, length: Dist) -> IPoint {
-        serde_wasm_bindgen::to_value(&self.0.eval(length))
+    pub fn transformed(&self, transform: ISimilarityTransform) -> Self {
+        let transform: SimilarityTransform =
+            serde_wasm_bindgen::from_value(transform.into()).unwrap();
+        Self(self.0.transformed(&(&transform).into()))
+    }
+
+    /// Returns the curve with the given parameterization, which determines
+    /// the parameters taken by [`JsCurve::at`] and the axes of parameter
+    /// space. Fails if timestamps are not nondecreasing or their number
+    /// differs from the number of points.
+    pub fn with_parameterization(
+        &self,
+        parameterization: IParameterization,
+    ) -> Result<JsCurve, JsValue> {
+        let parameterization: Parameterization =
+            serde_wasm_bindgen::from_value(parameterization.into())?;
+        self.0
+            .with_parameterization(parameterization)
+            .map(Self)
+            .ok_or_else(|| {
+                JsValue::from_str("there should be one timestamp per point, in nondecreasing order")
+            })
+    }
+
+    /// Returns the point at parameter `param`.
+    pub fn at(&self, param: Dist) -> IPoint {
+        serde_wasm_bindgen::to_value(&self.0.point_at_param(param))
             .unwrap()
             .into()
     }
@@ -70,12 +392,616 @@ impl JsCurve {
             .into()
     }
 
+    /// Points defining the curve, without the repeated first point of a closed
+    /// curve.
+    #[wasm_bindgen(getter)]
+    pub fn vertices(&self) -> IPoints {
+        serde_wasm_bindgen::to_value(self.0.vertices())
+            .unwrap()
+            .into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn cumulative_lengths(&self) -> ILengths {
+        serde_wasm_bindgen::to_value(self.0.cumulative_lengths())
+            .unwrap()
+            .into()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn parameterization(&self) -> IParameterization {
+        serde_wasm_bindgen::to_value(self.0.parameterization())
+            .unwrap()
+            .into()
+    }
+
+    /// Parameters at the points, which are their cumulative lengths unless the
+    /// curve was reparameterized.
+    #[wasm_bindgen(getter)]
+    pub fn cumulative_params(&self) -> ILengths {
+        serde_wasm_bindgen::to_value(self.0.cumulative_params())
+            .unwrap()
+            .into()
+    }
+
+    /// Returns whether both this curve and `other` have points, which the
+    /// continuous measures need.
+    fn has_points_with(&self, other: &JsCurve) -> bool {
+        !self.0.points().is_empty() && !other.0.points().is_empty()
+    }
+
+    /// Converts points `(l1, l2)` in arc-length parameter space of this curve
+    /// and `other` to their parameterizations.
+    fn lengths_to_params(
+        &self,
+        other: &Curve,
+        points: Vec<Point<Dist, 2>>,
+    ) -> Vec<Point<Dist, 2>> {
+        points
+            .into_iter()
+            .map(|point| {
+                Point::from([
+                    self.0.length_to_param(point.x),
+                    other.length_to_param(point.y),
+                ])
+            })
+            .collect()
+    }
+
+    /// Returns the Fréchet distance to `other`, or `undefined` if either curve
+    /// has no points.
+    pub fn frechet_dist(&self, other: &JsCurve) -> Option<Dist> {
+        self.has_points_with(other)
+            .then(|| frechet_dist([&self.0, &other.0]))
+    }
+
+    /// Returns the subcurve of `other` that best matches this entire curve
+    /// under the Fréchet distance, as parameters on `other`, or `undefined` if
+    /// either curve has no points.
+    pub fn partial_frechet(&self, other: &JsCurve) -> Option<IPartialFrechet> {
+        if !self.has_points_with(other) {
+            return None;
+        }
+        let result = partial_frechet([&self.0, &other.0]);
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsPartialFrechet {
+                dist: result.dist,
+                start_param: other.0.length_to_param(result.start_length),
+                end_param: other.0.length_to_param(result.end_length),
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+
+    /// Returns the Fréchet distance to the closed curve `other`, minimized over
+    /// all its start points, with a matching in the doubled parameter domain.
+    pub fn closed_frechet(&self, other: &JsCurve) -> IClosedFrechet {
+        let result = closed_frechet([&self.0, &other.0]);
+
+        serde_wasm_bindgen::to_value(&JsClosedFrechet {
+            dist: result.dist,
+            lower_bound: result.lower_bound,
+            start_param: other.0.length_to_param(result.start_length),
+            matching: self
+                .lengths_to_params(&other.0.doubled(), result.matching),
+        })
+        .unwrap()
+        .into()
+    }
+
+    /// Returns the translation of `other` that minimizes its Fréchet distance
+    /// to this curve, together with that distance.
+    pub fn translation_frechet(&self, other: &JsCurve) -> ITranslationFrechet {
+        serde_wasm_bindgen::to_value(&translation_frechet([&self.0, &other.0]))
+            .unwrap()
+            .into()
+    }
+
+    /// Returns the similarity transform of `other` that minimizes its distance
+    /// to this curve under `cost`, together with the residual distance.
+    pub fn similarity_alignment(
+        &self,
+        other: &JsCurve,
+        cost: ISimilarityCost,
+    ) -> ISimilarityAlignment {
+        let cost: SimilarityCost =
+            serde_wasm_bindgen::from_value(cost.into()).unwrap();
+        let result = similarity_alignment([&self.0, &other.0], cost);
+
+        serde_wasm_bindgen::to_value(&JsSimilarityAlignment {
+            dist: result.dist,
+            transform: (&result.transform).into(),
+        })
+        .unwrap()
+        .into()
+    }
+
+    /// Approximates the Fréchet distance to `other` where the leash has to go
+    /// around the polygonal `obstacles`, by the discrete Fréchet distance
+    /// between samples of both curves at most `resolution` apart.
+    pub fn geodesic_frechet(
+        &self,
+        other: &JsCurve,
+        obstacles: IPolygons,
+        resolution: Dist,
+    ) -> Dist {
+        let domain = PolygonalDomain::from_js(obstacles);
+        geodesic_frechet([&self.0, &other.0], &domain, resolution)
+    }
+
+    pub fn weak_frechet_dist(&self, other: &JsCurve) -> Dist {
+        weak_frechet_dist([&self.0, &other.0])
+    }
+
+    pub fn discrete_frechet(&self, other: &JsCurve) -> IDiscreteFrechet {
+        let result = discrete_frechet([&self.0, &other.0]);
+        let [params_1, params_2] =
+            [&self.0, &other.0].map(|curve| curve.cumulative_params());
+
+        serde_wasm_bindgen::to_value(&JsDiscreteFrechet {
+            dist: result.dist,
+            coupling: result
+                .coupling
+                .into_iter()
+                .map(|(i, j)| [params_1[i], params_2[j]])
+                .collect(),
+        })
+        .unwrap()
+        .into()
+    }
+
+    /// Returns the DTW cost and warping path, or `undefined` if the
+    /// constraints in `options` exclude every warping path.
+    pub fn dtw(&self, other: &JsCurve, options: IDtwOptions) -> Option<IDtw> {
+        let options: DtwOptions =
+            serde_wasm_bindgen::from_value(options.into()).unwrap();
+        let result = dtw([&self.0, &other.0], &options)?;
+        let [params_1, params_2] =
+            [&self.0, &other.0].map(|curve| curve.cumulative_params());
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsDtw {
+                cost: result.cost,
+                path: result
+                    .path
+                    .into_iter()
+                    .map(|(i, j)| [params_1[i], params_2[j]])
+                    .collect(),
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+
+    /// Returns the smallest integral of the leash length over monotone paths
+    /// on a grid with spacing at most `resolution`, its average over the path
+    /// and the minimizing path, or `undefined` if either curve has no points.
+    pub fn integral_frechet(
+        &self,
+        other: &JsCurve,
+        resolution: Dist,
+    ) -> Option<IIntegralFrechet> {
+        if !self.has_points_with(other) {
+            return None;
+        }
+        let result = integral_frechet([&self.0, &other.0], resolution);
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsIntegralFrechet {
+                integral: result.integral,
+                average: result.average,
+                path: self.lengths_to_params(&other.0, result.path),
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+
+    /// Returns the integral of the leash length along the monotone `path` in
+    /// parameter space.
+    pub fn path_cost(
+        &self,
+        other: &JsCurve,
+        path: IPoints,
+        resolution: Dist,
+    ) -> Dist {
+        let path: Vec<Point<Dist, 2>> =
+            serde_wasm_bindgen::from_value(path.into()).unwrap();
+        let path = path
+            .into_iter()
+            .map(|point| {
+                Point::from([
+                    self.0.param_to_length(point.x),
+                    other.0.param_to_length(point.y),
+                ])
+            })
+            .collect_vec();
+        path_cost([&self.0, &other.0], &path, resolution)
+    }
+
+    pub fn lcss(&self, other: &JsCurve, epsilon: Dist) -> IAlignment {
+        serde_wasm_bindgen::to_value(&lcss([&self.0, &other.0], epsilon))
+            .unwrap()
+            .into()
+    }
+
+    pub fn edr(&self, other: &JsCurve, epsilon: Dist) -> IAlignment {
+        serde_wasm_bindgen::to_value(&edr([&self.0, &other.0], epsilon))
+            .unwrap()
+            .into()
+    }
+
+    pub fn erp(&self, other: &JsCurve, gap: IPoint) -> IAlignment {
+        let gap = serde_wasm_bindgen::from_value(gap.into()).unwrap();
+        serde_wasm_bindgen::to_value(&erp([&self.0, &other.0], &gap))
+            .unwrap()
+            .into()
+    }
+
+    /// Returns the Hausdorff distance to `other`, with the witness points
+    /// located by their parameters on the curves, or `undefined` if either
+    /// curve has no points.
+    pub fn hausdorff(&self, other: &JsCurve) -> Option<IHausdorff> {
+        if !self.has_points_with(other) {
+            return None;
+        }
+        let result = hausdorff(&self.0, &other.0);
+        let [length_1, length_2] = result.witness_lengths;
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsHausdorff {
+                dist: result.dist,
+                witness_points: result.witness_points,
+                witness_params: [
+                    self.0.length_to_param(length_1),
+                    other.0.length_to_param(length_2),
+                ],
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+}
+
+/// Curve in any dimension up to 8, e.g. a 3D flight path or a trajectory of
+/// feature vectors. Curves can only be compared with curves of the same
+/// dimension.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct JsCurveNd(AnyCurve);
+
+#[wasm_bindgen]
+impl JsCurveNd {
+    #[wasm_bindgen(constructor)]
+    pub fn new(points: IPointsNd) -> Result<JsCurveNd, JsValue> {
+        let points: Vec<Vec<Dist>> =
+            serde_wasm_bindgen::from_value(points.into())?;
+        AnyCurve::from_points(points).map(Self).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "points should all have between 1 and {MAX_DIMENSION} \
+                coordinates, since each supported dimension is compiled \
+                separately; reduce higher-dimensional data first, e.g. by PCA"
+            ))
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dimension(&self) -> usize {
+        self.0.dimension()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn points(&self) -> IPointsNd {
+        serde_wasm_bindgen::to_value(&self.0.points())
+            .unwrap()
+            .into()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn cumulative_lengths(&self) -> ILengths {
         serde_wasm_bindgen::to_value(self.0.cumulative_lengths())
             .unwrap()
             .into()
     }
+
+    /// Returns the Fréchet distance to `other`, or `undefined` if the curves
+    /// have different dimensions.
+    pub fn frechet_dist(&self, other: &JsCurveNd) -> Option<Dist> {
+        with_curve_pair!([&self.0, &other.0], curves => frechet_dist(curves))
+    }
+
+    /// Returns the discrete Fréchet distance to `other` with an optimal
+    /// coupling, or `undefined` if the curves have different dimensions.
+    pub fn discrete_frechet(
+        &self,
+        other: &JsCurveNd,
+    ) -> Option<IDiscreteFrechet> {
+        let result = with_curve_pair!([&self.0, &other.0], curves => {
+            discrete_frechet(curves)
+        })?;
+        let [params_1, params_2] =
+            [&self.0, &other.0].map(|curve| curve.cumulative_params());
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsDiscreteFrechet {
+                dist: result.dist,
+                coupling: result
+                    .coupling
+                    .into_iter()
+                    .map(|(i, j)| [params_1[i], params_2[j]])
+                    .collect(),
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+
+    /// Returns the weak Fréchet distance to `other`, or `undefined` if the
+    /// curves have different dimensions.
+    pub fn weak_frechet_dist(&self, other: &JsCurveNd) -> Option<Dist> {
+        with_curve_pair!([&self.0, &other.0], curves => {
+            weak_frechet_dist(curves)
+        })
+    }
+
+    /// Returns the DTW cost and warping path, or `undefined` if the curves
+    /// have different dimensions or the constraints in `options` exclude
+    /// every warping path.
+    pub fn dtw(&self, other: &JsCurveNd, options: IDtwOptions) -> Option<IDtw> {
+        let options: DtwOptions =
+            serde_wasm_bindgen::from_value(options.into()).unwrap();
+        let result = with_curve_pair!([&self.0, &other.0], curves => {
+            dtw(curves, &options)
+        })??;
+        let [params_1, params_2] =
+            [&self.0, &other.0].map(|curve| curve.cumulative_params());
+
+        Some(
+            serde_wasm_bindgen::to_value(&JsDtw {
+                cost: result.cost,
+                path: result
+                    .path
+                    .into_iter()
+                    .map(|(i, j)| [params_1[i], params_2[j]])
+                    .collect(),
+            })
+            .unwrap()
+            .into(),
+        )
+    }
+
+    /// Returns the free-space diagram with `other` for `epsilon`, or
+    /// `undefined` if the curves have different dimensions.
+    pub fn free_space_diagram(
+        &self,
+        other: &JsCurveNd,
+        epsilon: Dist,
+    ) -> Option<JsFreeSpaceDiagram> {
+        let diagram = with_curve_pair!([&self.0, &other.0], curves => {
+            FreeSpaceDiagram::new(curves, epsilon)
+        })?;
+        Some(JsFreeSpaceDiagram {
+            curves: [self.0.clone(), other.0.clone()],
+            diagram,
+        })
+    }
+}
+
+impl From<JsCurveNd> for AnyCurve {
+    fn from(js_curve: JsCurveNd) -> Self {
+        js_curve.0
+    }
+}
+
+/// Free-space diagram of two curves for a fixed epsilon, with free intervals
+/// given as parameters on the curves.
+#[wasm_bindgen]
+pub struct JsFreeSpaceDiagram {
+    curves: [AnyCurve; 2],
+    diagram: FreeSpaceDiagram,
+}
+
+#[wasm_bindgen]
+impl JsFreeSpaceDiagram {
+    #[wasm_bindgen(constructor)]
+    pub fn new(curve_1: &JsCurve, curve_2: &JsCurve, epsilon: Dist) -> Self {
+        let diagram = FreeSpaceDiagram::new([&curve_1.0, &curve_2.0], epsilon);
+        Self {
+            curves: [curve_1.0.clone().into(), curve_2.0.clone().into()],
+            diagram,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn epsilon(&self) -> Dist {
+        self.diagram.epsilon()
+    }
+
+    /// Number of cells along the first curve, i.e. its number of segments.
+    #[wasm_bindgen(getter)]
+    pub fn num_columns(&self) -> usize {
+        self.diagram.shape().0
+    }
+
+    /// Number of cells along the second curve, i.e. its number of segments.
+    #[wasm_bindgen(getter)]
+    pub fn num_rows(&self) -> usize {
+        self.diagram.shape().1
+    }
+
+    /// Returns the free intervals on the boundaries of cell `(i, j)`, as
+    /// parameters on the second curve for the left and right boundaries and
+    /// on the first curve for the bottom and top boundaries.
+    pub fn cell(&self, i: usize, j: usize) -> IFreeCell {
+        let FreeCell {
+            left,
+            right,
+            bottom,
+            top,
+        } = self.diagram.cell(i, j);
+        let [curve_1, curve_2] = &self.curves;
+        let params = |curve: &AnyCurve,
+                      segment_idx: usize,
+                      interval: Interval| {
+            interval.map(|interval| {
+                interval.map(|t| curve.segment_param_to_param(segment_idx, t))
+            })
+        };
+
+        serde_wasm_bindgen::to_value(&JsFreeCell {
+            left: params(curve_2, j, left),
+            right: params(curve_2, j, right),
+            bottom: params(curve_1, i, bottom),
+            top: params(curve_1, i, top),
+        })
+        .unwrap()
+        .into()
+    }
+}
+
+impl PolygonalDomain {
+    pub fn from_js(obstacles: IPolygons) -> Self {
+        Self::new(serde_wasm_bindgen::from_value(obstacles.into()).unwrap())
+    }
+}
+
+/// Returns a shortest path from `from` to `to` around the polygonal
+/// `obstacles`, or `undefined` if there is none.
+#[wasm_bindgen]
+pub fn geodesic_path(
+    obstacles: IPolygons,
+    from: IPoint,
+    to: IPoint,
+) -> Option<IPoints> {
+    let domain = PolygonalDomain::from_js(obstacles);
+    let [from, to]: [Point<Dist, 2>; 2] = [from, to]
+        .map(|point| serde_wasm_bindgen::from_value(point.into()).unwrap());
+
+    let path = domain.shortest_path(&from, &to)?;
+    Some(serde_wasm_bindgen::to_value(&path).unwrap().into())
+}
+
+/// Returns a curve with `num_vertices` vertices that minimizes the maximum
+/// (`'center'`) or sum (`'median'`) of Fréchet distances to the curves through
+/// the given points, together with that maximum or sum, or returns `undefined`
+/// if no curves are given or one of them has no points.
+#[wasm_bindgen(js_name = frechet_mean)]
+pub fn js_frechet_mean(
+    curves: ICurvesPoints,
+    objective: IMeanObjective,
+    num_vertices: usize,
+) -> Option<IFrechetMean> {
+    let curves = curves_from_js(curves);
+    let objective: MeanObjective =
+        serde_wasm_bindgen::from_value(objective.into()).unwrap();
+
+    let inputs = curves.iter().collect_vec();
+    let result = frechet_mean(&inputs, objective, num_vertices)?;
+
+    Some(
+        serde_wasm_bindgen::to_value(&JsFrechetMean {
+            points: result.curve.points(),
+            cost: result.cost,
+        })
+        .unwrap()
+        .into(),
+    )
+}
+
+fn curves_from_js(curves: ICurvesPoints) -> Vec<Curve> {
+    let curves: Vec<Vec<Point<Dist, 2>>> =
+        serde_wasm_bindgen::from_value(curves.into()).unwrap();
+    curves.into_iter().map(Curve::from_points).collect()
+}
+
+/// Clusters the curves through the given points around `k` of them, such that
+/// the largest `distance` from a curve to its cluster center is small, or
+/// returns `undefined` if `k` is 0 or no curves are given.
+#[wasm_bindgen(js_name = k_center)]
+pub fn js_k_center(
+    curves: ICurvesPoints,
+    k: usize,
+    distance: ICurveDistance,
+) -> Option<IClustering> {
+    let curves = curves_from_js(curves);
+    let distance: CurveDistance =
+        serde_wasm_bindgen::from_value(distance.into()).unwrap();
+
+    let result = k_center(&curves.iter().collect_vec(), k, |curves| {
+        distance.eval(curves)
+    })?;
+    Some(serde_wasm_bindgen::to_value(&result).unwrap().into())
+}
+
+/// Clusters the curves through the given points around `k` of them, such that
+/// the total `distance` from the curves to their cluster centers is small, or
+/// returns `undefined` if `k` is 0 or no curves are given.
+#[wasm_bindgen(js_name = k_medians)]
+pub fn js_k_medians(
+    curves: ICurvesPoints,
+    k: usize,
+    distance: ICurveDistance,
+    max_swaps: usize,
+) -> Option<IClustering> {
+    let curves = curves_from_js(curves);
+    let distance: CurveDistance =
+        serde_wasm_bindgen::from_value(distance.into()).unwrap();
+
+    let result = k_medians(
+        &curves.iter().collect_vec(),
+        k,
+        |curves| distance.eval(curves),
+        max_swaps,
+    )?;
+    Some(serde_wasm_bindgen::to_value(&result).unwrap().into())
+}
+
+/// Returns the path in the graph on `vertices` and `edges` with the smallest
+/// Fréchet distance to `curve`, as a sequence of vertex indices, or
+/// `undefined` if the graph has no vertices.
+#[wasm_bindgen(js_name = map_match)]
+pub fn js_map_match(
+    vertices: IPoints,
+    edges: IEdges,
+    curve: &JsCurve,
+) -> Option<IMapMatch> {
+    let graph = PlanarGraph::new(
+        serde_wasm_bindgen::from_value(vertices.into()).unwrap(),
+        serde_wasm_bindgen::from_value(edges.into()).unwrap(),
+    );
+
+    let result = map_match(&curve.0, &graph)?;
+    Some(serde_wasm_bindgen::to_value(&result).unwrap().into())
+}
+
+#[wasm_bindgen]
+pub struct JsCurveIndex(CurveIndex);
+
+#[wasm_bindgen]
+impl JsCurveIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(curves: ICurvesPoints) -> Self {
+        Self(CurveIndex::new(curves_from_js(curves)))
+    }
+
+    pub fn push(&mut self, curve: &JsCurve) {
+        self.0.push(curve.0.clone());
+    }
+
+    /// Returns the index of and Fréchet distance to the stored curve closest
+    /// to `query`, with statistics on how the other curves were pruned, or
+    /// `undefined` if the index is empty.
+    pub fn nearest(&self, query: &JsCurve) -> Option<INearestNeighbor> {
+        let result = self.0.nearest(&query.0)?;
+        Some(serde_wasm_bindgen::to_value(&result).unwrap().into())
+    }
 }
 
 impl From<JsCurve> for Curve {
@@ -0,0 +1,42 @@
+use nalgebra::Point;
+
+use crate::geom::curve::Curve;
+use crate::geom::Dist;
+
+/// Graph embedded in the plane, with straight edges between its vertices.
+#[derive(Default, Clone)]
+pub struct PlanarGraph {
+    vertices: Vec<Point<Dist, 2>>,
+    /// Indices of the vertices adjacent to each vertex
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl PlanarGraph {
+    /// Creates a graph on `vertices` with undirected `edges` given as pairs of
+    /// vertex indices.
+    pub fn new(vertices: Vec<Point<Dist, 2>>, edges: Vec<[usize; 2]>) -> Self {
+        let mut neighbors = vec![vec![]; vertices.len()];
+        for &[u, v] in &edges {
+            neighbors[u].push(v);
+            neighbors[v].push(u);
+        }
+
+        Self {
+            vertices,
+            neighbors,
+        }
+    }
+
+    pub fn vertices(&self) -> &Vec<Point<Dist, 2>> {
+        &self.vertices
+    }
+
+    pub fn neighbors(&self, vertex: usize) -> &Vec<usize> {
+        &self.neighbors[vertex]
+    }
+
+    /// Returns the curve through the vertices with the given indices.
+    pub fn path_curve(&self, path: &[usize]) -> Curve {
+        Curve::from_points(path.iter().map(|&idx| self.vertices[idx]).collect())
+    }
+}
@@ -0,0 +1,407 @@
+use itertools::Itertools;
+use nalgebra::{Point, Vector2};
+
+use crate::geom::line_segment::LineSegment;
+use crate::geom::metric::{Metric, PointMetric};
+use crate::geom::Dist;
+
+/// Distance below which points are considered to lie on a segment or on the
+/// boundary of an obstacle.
+const TOLERANCE: Dist = 1e-5;
+
+/// Number of pieces of equal length into which [`Metric::max_dist`] divides
+/// each polyline to sample it.
+const MAX_DIST_PIECES: usize = 32;
+
+/// Returns twice the signed area of the triangle `(a, b, c)`, which is positive
+/// if the triangle is oriented counterclockwise.
+fn orientation(
+    a: &Point<Dist, 2>,
+    b: &Point<Dist, 2>,
+    c: &Point<Dist, 2>,
+) -> Dist {
+    (b - a).perp(&(c - a))
+}
+
+/// Returns whether the segments `ab` and `cd` cross in a single point that is
+/// interior to both.
+fn properly_intersect(
+    [a, b]: [&Point<Dist, 2>; 2],
+    [c, d]: [&Point<Dist, 2>; 2],
+) -> bool {
+    orientation(a, b, c) * orientation(a, b, d) < 0.
+        && orientation(c, d, a) * orientation(c, d, b) < 0.
+}
+
+/// Returns the vertices of the polyline through `points` together with points
+/// subdividing its segments into pieces no longer than `spacing`.
+fn sample_polyline(
+    points: &[Point<Dist, 2>],
+    spacing: Dist,
+) -> Vec<Point<Dist, 2>> {
+    points
+        .iter()
+        .take(1)
+        .copied()
+        .chain(points.iter().tuple_windows().flat_map(|(p, q)| {
+            let num_pieces = ((q - p).norm() / spacing).ceil().max(1.) as usize;
+            (1..=num_pieces)
+                .map(move |i| p + (q - p) * (i as Dist / num_pieces as Dist))
+        }))
+        .collect()
+}
+
+/// The plane with polygonal obstacles (holes) removed, in which distances are
+/// measured along shortest paths that avoid the interiors of the obstacles.
+///
+/// Shortest paths bend only at obstacle vertices, so they are found in the
+/// visibility graph on those vertices, whose all-pairs shortest paths are
+/// precomputed.
+#[derive(Default, Clone)]
+pub struct PolygonalDomain {
+    /// Obstacle polygons, each given by its vertices in order
+    obstacles: Vec<Vec<Point<Dist, 2>>>,
+    /// Vertices of all obstacles, i.e. the nodes of the visibility graph
+    vertices: Vec<Point<Dist, 2>>,
+    /// Shortest path distances between vertices `i` and `j`, stored at index
+    /// `i * n + j`
+    vertex_dists: Vec<Dist>,
+    /// Vertex following `i` on the shortest path from `i` to `j`, stored at
+    /// index `i * n + j`
+    next_vertices: Vec<Option<usize>>,
+}
+
+impl PolygonalDomain {
+    pub fn new(obstacles: Vec<Vec<Point<Dist, 2>>>) -> Self {
+        let vertices = obstacles.iter().flatten().copied().collect_vec();
+        let n = vertices.len();
+
+        let mut domain = Self {
+            obstacles,
+            vertices,
+            vertex_dists: vec![Dist::INFINITY; n * n],
+            next_vertices: vec![None; n * n],
+        };
+
+        for i in 0..n {
+            domain.vertex_dists[i * n + i] = 0.;
+            domain.next_vertices[i * n + i] = Some(i);
+        }
+        for (i, j) in (0..n).tuple_combinations() {
+            let [u, v] = [domain.vertices[i], domain.vertices[j]];
+            if domain.is_visible([&u, &v], &[]) {
+                let dist = (u - v).norm();
+                domain.vertex_dists[i * n + j] = dist;
+                domain.vertex_dists[j * n + i] = dist;
+                domain.next_vertices[i * n + j] = Some(j);
+                domain.next_vertices[j * n + i] = Some(i);
+            }
+        }
+
+        // Floyd-Warshall
+        for k in 0..n {
+            for (i, j) in Itertools::cartesian_product(0..n, 0..n) {
+                let dist = domain.vertex_dists[i * n + k]
+                    + domain.vertex_dists[k * n + j];
+                if dist < domain.vertex_dists[i * n + j] {
+                    domain.vertex_dists[i * n + j] = dist;
+                    domain.next_vertices[i * n + j] =
+                        domain.next_vertices[i * n + k];
+                }
+            }
+        }
+
+        domain
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+
+    fn obstacle_edges(
+        &self,
+        obstacle_idx: usize,
+    ) -> impl Iterator<Item = [&Point<Dist, 2>; 2]> + '_ {
+        let obstacle = &self.obstacles[obstacle_idx];
+        obstacle
+            .iter()
+            .zip(obstacle.iter().cycle().skip(1))
+            .map(|(a, b)| [a, b])
+    }
+
+    /// Returns whether `point` lies in the interior of the obstacle with index
+    /// `obstacle_idx`, at least [`TOLERANCE`] away from its boundary.
+    fn obstacle_contains(
+        &self,
+        obstacle_idx: usize,
+        point: &Point<Dist, 2>,
+    ) -> bool {
+        let mut inside = false;
+        for [a, b] in self.obstacle_edges(obstacle_idx) {
+            let edge = LineSegment::from((*a, *b));
+            if edge.point_dist_squared(point) <= TOLERANCE * TOLERANCE {
+                return false;
+            }
+
+            // Ray casting towards positive x
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Returns the indices of the obstacles containing `point`.
+    fn containing_obstacles(&self, point: &Point<Dist, 2>) -> Vec<usize> {
+        (0..self.obstacles.len())
+            .filter(|&obstacle_idx| self.obstacle_contains(obstacle_idx, point))
+            .collect()
+    }
+
+    /// Returns whether the segment `ab` avoids the interiors of all obstacles
+    /// except the `ignored` ones. The segment may touch obstacle boundaries
+    /// and pass through obstacle vertices.
+    fn is_visible(
+        &self,
+        [a, b]: [&Point<Dist, 2>; 2],
+        ignored: &[usize],
+    ) -> bool {
+        let obstacle_indices = (0..self.obstacles.len())
+            .filter(|obstacle_idx| !ignored.contains(obstacle_idx))
+            .collect_vec();
+
+        if obstacle_indices.iter().any(|&obstacle_idx| {
+            self.obstacle_edges(obstacle_idx)
+                .any(|edge| properly_intersect([a, b], edge))
+        }) {
+            return false;
+        }
+
+        // Without proper crossings, the segment can only enter an obstacle
+        // through its vertices, so check each piece between vertices on the
+        // segment
+        let segment = LineSegment::from((*a, *b));
+        let params = obstacle_indices
+            .iter()
+            .flat_map(|&obstacle_idx| &self.obstacles[obstacle_idx])
+            .filter(|v| segment.point_dist_squared(v) <= TOLERANCE * TOLERANCE)
+            .map(|v| segment.closest_param(v))
+            .chain([0., 1.])
+            .sorted_by(Dist::total_cmp)
+            .collect_vec();
+
+        params.iter().tuple_windows().all(|(&t1, &t2)| {
+            let midpoint = segment.point_at((t1 + t2) / 2.);
+            obstacle_indices.iter().all(|&obstacle_idx| {
+                !self.obstacle_contains(obstacle_idx, &midpoint)
+            })
+        })
+    }
+
+    /// Returns the indices of the vertices visible from `point`, where the
+    /// obstacles containing `point` don't block its view.
+    fn visible_vertices(&self, point: &Point<Dist, 2>) -> Vec<usize> {
+        let ignored = self.containing_obstacles(point);
+        (0..self.vertices.len())
+            .filter(|&idx| {
+                self.is_visible([point, &self.vertices[idx]], &ignored)
+            })
+            .collect()
+    }
+
+    /// Returns the length of a shortest path from `p` to `q`, and the first
+    /// and last obstacle vertex on it unless the path is a straight segment.
+    fn route(
+        &self,
+        p: &Point<Dist, 2>,
+        q: &Point<Dist, 2>,
+    ) -> Option<(Dist, Option<(usize, usize)>)> {
+        let ignored = [p, q]
+            .into_iter()
+            .flat_map(|point| self.containing_obstacles(point))
+            .collect_vec();
+        if self.is_visible([p, q], &ignored) {
+            return Some(((p - q).norm(), None));
+        }
+
+        let n = self.vertices.len();
+        let [visible_p, visible_q] =
+            [p, q].map(|point| self.visible_vertices(point));
+
+        Itertools::cartesian_product(visible_p.into_iter(), visible_q.iter())
+            .map(|(u, &v)| {
+                let dist = (p - self.vertices[u]).norm()
+                    + self.vertex_dists[u * n + v]
+                    + (self.vertices[v] - q).norm();
+                (dist, Some((u, v)))
+            })
+            .filter(|(dist, _)| dist.is_finite())
+            .min_by(|(dist_1, _), (dist_2, _)| Dist::total_cmp(dist_1, dist_2))
+    }
+
+    /// Returns the length of a shortest path from `p` to `q` that avoids the
+    /// obstacles, or infinity if there is none. Obstacles containing `p` or
+    /// `q` don't block paths leaving them.
+    pub fn dist(&self, p: &Point<Dist, 2>, q: &Point<Dist, 2>) -> Dist {
+        self.route(p, q).map_or(Dist::INFINITY, |(dist, _)| dist)
+    }
+
+    /// Returns a shortest path from `p` to `q` that avoids the obstacles, as a
+    /// polyline, or `None` if there is none.
+    pub fn shortest_path(
+        &self,
+        p: &Point<Dist, 2>,
+        q: &Point<Dist, 2>,
+    ) -> Option<Vec<Point<Dist, 2>>> {
+        let n = self.vertices.len();
+        let (_, vertices) = self.route(p, q)?;
+
+        let mut path = vec![*p];
+        if let Some((mut u, v)) = vertices {
+            path.push(self.vertices[u]);
+            while u != v {
+                u = self.next_vertices[u * n + v]?;
+                path.push(self.vertices[u]);
+            }
+        }
+        path.push(*q);
+        Some(path)
+    }
+}
+
+/// Geodesic distances are not convex, so the bounds used to search for the
+/// closest segments are the straight-line distances, which never exceed them.
+impl Metric<2> for PolygonalDomain {
+    fn dist(&self, p: &Point<Dist, 2>, q: &Point<Dist, 2>) -> Dist {
+        PolygonalDomain::dist(self, p, q)
+    }
+
+    /// Returns the unit vector pointing away from the next point on a shortest
+    /// path from `p` to `q`, or zero if there is no such path.
+    fn dist_gradient(
+        &self,
+        p: &Point<Dist, 2>,
+        q: &Point<Dist, 2>,
+    ) -> Vector2<Dist> {
+        let next = match self.route(p, q) {
+            Some((_, Some((u, _)))) => self.vertices[u],
+            Some((_, None)) => *q,
+            None => return Vector2::zeros(),
+        };
+        (p - next).try_normalize(0.).unwrap_or_else(Vector2::zeros)
+    }
+
+    fn point_segment_dist(
+        &self,
+        point: &Point<Dist, 2>,
+        segment: &LineSegment<Dist, 2>,
+    ) -> Dist {
+        PointMetric::Euclidean.point_segment_dist(point, segment)
+    }
+
+    fn box_dist(
+        &self,
+        box_1: &[Point<Dist, 2>; 2],
+        box_2: &[Point<Dist, 2>; 2],
+    ) -> Dist {
+        PointMetric::Euclidean.box_dist(box_1, box_2)
+    }
+
+    fn segment_dist(
+        &self,
+        l1: &LineSegment<Dist, 2>,
+        l2: &LineSegment<Dist, 2>,
+    ) -> Dist {
+        l1.dist(l2)
+    }
+
+    /// Returns an upper bound on the largest geodesic distance between points
+    /// on the polylines through `points_1` and `points_2`, rather than between
+    /// their convex hulls.
+    ///
+    /// The largest distance need not be attained at vertices, e.g. where a
+    /// segment passes behind an obstacle, so both polylines are sampled with
+    /// some spacing. As long as the polylines avoid the obstacles, moving
+    /// along them changes the distance by at most the distance moved, so
+    /// adding half of each spacing to the largest distance between samples
+    /// bounds the distance between all points.
+    fn max_dist(
+        &self,
+        points_1: &[Point<Dist, 2>],
+        points_2: &[Point<Dist, 2>],
+    ) -> Dist {
+        let [(samples_1, spacing_1), (samples_2, spacing_2)] =
+            [points_1, points_2].map(|points| {
+                let length = points
+                    .iter()
+                    .tuple_windows()
+                    .map(|(p, q)| (q - p).norm())
+                    .sum::<Dist>();
+                let spacing = length / MAX_DIST_PIECES as Dist;
+                (sample_polyline(points, spacing), spacing)
+            });
+
+        Itertools::cartesian_product(samples_1.iter(), samples_2.iter())
+            .map(|(p1, p2)| PolygonalDomain::dist(self, p1, p2))
+            .fold(Dist::NEG_INFINITY, Dist::max)
+            + (spacing_1 + spacing_2) / 2.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn around_square() {
+        let domain = PolygonalDomain::new(vec![vec![
+            point![1.0, -1.0],
+            point![2.0, -1.0],
+            point![2.0, 1.0],
+            point![1.0, 1.0],
+        ]]);
+
+        let [p, q] = [point![0.0, 0.0], point![3.0, 0.0]];
+        let expected = 2. * Dist::sqrt(2.) + 1.;
+        assert_relative_eq!(domain.dist(&p, &q), expected, epsilon = 1e-4);
+
+        let path = domain.shortest_path(&p, &q).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_relative_eq!(path[1].x, 1.0);
+        assert_relative_eq!(path[2].x, 2.0);
+
+        // Unobstructed, and along an obstacle edge
+        let r = point![0.0, 1.0];
+        assert_relative_eq!(domain.dist(&p, &r), 1.0);
+        assert_relative_eq!(
+            domain.dist(&point![1.0, 1.0], &point![2.0, 1.0]),
+            1.0
+        );
+    }
+
+    #[test]
+    fn max_dist_behind_wall() {
+        let domain = PolygonalDomain::new(vec![vec![
+            point![9.0, -0.8],
+            point![9.1, -0.8],
+            point![9.1, 0.8],
+            point![9.0, 0.8],
+        ]]);
+
+        // Both ends of the segment are visible from `p`, but its midpoint is
+        // hidden behind the wall and farther away
+        let p = point![0.0, 0.0];
+        let segment = [point![10.0, -1.0], point![10.0, 1.0]];
+        let midpoint_dist = domain.dist(&p, &point![10.0, 0.0]);
+        assert!(midpoint_dist > domain.dist(&p, &segment[0]));
+
+        let max_dist = Metric::max_dist(&domain, &[p], &segment);
+        assert!(max_dist >= midpoint_dist);
+        assert!(max_dist <= midpoint_dist + 0.1);
+    }
+}
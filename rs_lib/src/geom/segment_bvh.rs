@@ -0,0 +1,325 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+use itertools::{Either, Itertools};
+use nalgebra::Point;
+
+use crate::geom::line_segment::LineSegment;
+use crate::geom::metric::Metric;
+use crate::geom::Dist;
+
+/// Largest number of segments stored in a leaf.
+const LEAF_SIZE: usize = 4;
+
+/// Axis-aligned bounding box, as its minimum and maximum corners.
+type BoundingBox<const D: usize> = [Point<Dist, D>; 2];
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    /// Range of `SegmentBvh::segments` contained in the leaf
+    Leaf(Range<usize>),
+    /// Indices of the two child nodes
+    Inner([usize; 2]),
+}
+
+#[derive(Debug, Clone)]
+struct Node<const D: usize> {
+    bounds: BoundingBox<D>,
+    kind: NodeKind,
+}
+
+/// Bounding-volume hierarchy over the segments of a curve, for finding the
+/// closest pair of segments between two curves without comparing all pairs.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentBvh<const D: usize = 2> {
+    /// Endpoints of the segments, ordered such that each node contains a
+    /// contiguous range of them
+    segments: Vec<[Point<Dist, D>; 2]>,
+    /// Nodes of the hierarchy, with the root first
+    nodes: Vec<Node<D>>,
+}
+
+impl<const D: usize> SegmentBvh<D> {
+    pub fn new(mut segments: Vec<[Point<Dist, D>; 2]>) -> Self {
+        let mut nodes = vec![];
+        if !segments.is_empty() {
+            let len = segments.len();
+            Self::build(&mut segments, 0..len, &mut nodes);
+        }
+        Self { segments, nodes }
+    }
+
+    /// Builds the subtree over `segments[range]` by splitting the segments at
+    /// the median of their midpoints along the longer side of their bounding
+    /// box, and returns the index of its root.
+    fn build(
+        segments: &mut [[Point<Dist, D>; 2]],
+        range: Range<usize>,
+        nodes: &mut Vec<Node<D>>,
+    ) -> usize {
+        let [first, _] = segments[range.start];
+        let bounds = segments[range.clone()]
+            .iter()
+            .flatten()
+            .fold([first, first], |[min, max], point| {
+                [min.inf(point), max.sup(point)]
+            });
+
+        let node_idx = nodes.len();
+        nodes.push(Node {
+            bounds,
+            kind: NodeKind::Leaf(range.clone()),
+        });
+        if range.len() <= LEAF_SIZE {
+            return node_idx;
+        }
+
+        let [min, max] = bounds;
+        let axis = (max - min).imax();
+        let mid = range.start + range.len() / 2;
+        segments[range.clone()].select_nth_unstable_by(
+            mid - range.start,
+            |[p1, q1], [p2, q2]| {
+                Dist::total_cmp(&(p1[axis] + q1[axis]), &(p2[axis] + q2[axis]))
+            },
+        );
+
+        let children = [
+            Self::build(segments, range.start..mid, nodes),
+            Self::build(segments, mid..range.end, nodes),
+        ];
+        nodes[node_idx].kind = NodeKind::Inner(children);
+        node_idx
+    }
+
+    /// Returns the smallest distance in `metric` between a segment in this
+    /// hierarchy and one in `other`, or infinity if either is empty.
+    ///
+    /// Pairs of nodes are visited in order of the distance between their
+    /// bounding boxes, and pairs whose boxes are farther apart than the
+    /// closest pair of segments found so far are skipped.
+    pub fn min_dist(&self, other: &Self, metric: &dyn Metric<D>) -> Dist {
+        if self.nodes.is_empty() || other.nodes.is_empty() {
+            return Dist::INFINITY;
+        }
+
+        let bvhs = [self, other];
+        let candidate = |node_indices: [usize; 2]| {
+            let [bounds_1, bounds_2] =
+                [0, 1].map(|idx| &bvhs[idx].nodes[node_indices[idx]].bounds);
+            NodePair {
+                lower_bound: metric.box_dist(bounds_1, bounds_2),
+                node_indices,
+            }
+        };
+
+        let mut min_dist = Dist::INFINITY;
+        let mut queue = BinaryHeap::from([candidate([0, 0])]);
+        while let Some(NodePair {
+            lower_bound,
+            node_indices,
+        }) = queue.pop()
+        {
+            if lower_bound >= min_dist {
+                break;
+            }
+
+            let [node_1, node_2] =
+                [0, 1].map(|idx| &bvhs[idx].nodes[node_indices[idx]]);
+            // Descend into the larger inner node
+            let split_idx = match [&node_1.kind, &node_2.kind] {
+                [NodeKind::Leaf(range_1), NodeKind::Leaf(range_2)] => {
+                    let segments_1 = &self.segments[range_1.clone()];
+                    let segments_2 = &other.segments[range_2.clone()];
+                    for ([p1, q1], [p2, q2]) in Itertools::cartesian_product(
+                        segments_1.iter(),
+                        segments_2,
+                    ) {
+                        let dist = metric.segment_dist(
+                            &LineSegment::from((*p1, *q1)),
+                            &LineSegment::from((*p2, *q2)),
+                        );
+                        min_dist = min_dist.min(dist);
+                    }
+                    continue;
+                }
+                [NodeKind::Inner(_), NodeKind::Leaf(_)] => 0,
+                [NodeKind::Leaf(_), NodeKind::Inner(_)] => 1,
+                [NodeKind::Inner(_), NodeKind::Inner(_)] => {
+                    if box_size(&node_1.bounds) >= box_size(&node_2.bounds) {
+                        0
+                    } else {
+                        1
+                    }
+                }
+            };
+
+            let NodeKind::Inner(children) = [node_1, node_2][split_idx].kind
+            else {
+                unreachable!()
+            };
+            for child in children {
+                let mut child_indices = node_indices;
+                child_indices[split_idx] = child;
+                queue.push(candidate(child_indices));
+            }
+        }
+
+        min_dist
+    }
+}
+
+fn box_size<const D: usize>([min, max]: &BoundingBox<D>) -> Dist {
+    (max - min).norm_squared()
+}
+
+/// Pair of nodes from two hierarchies, ordered such that the pair with the
+/// smallest lower bound on the distance comes first.
+struct NodePair {
+    lower_bound: Dist,
+    node_indices: [usize; 2],
+}
+
+impl PartialEq for NodePair {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for NodePair {}
+
+impl PartialOrd for NodePair {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NodePair {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Dist::total_cmp(&other.lower_bound, &self.lower_bound)
+    }
+}
+
+/// Returns the points among `points` at which a convex function, such as the
+/// distance to any point, can attain its maximum over their convex hull: the
+/// vertices of the hull for planar points (using Andrew's monotone chain
+/// algorithm, in counterclockwise order), or all points in other dimensions.
+pub fn extreme_points<const D: usize>(
+    points: &[Point<Dist, D>],
+) -> Vec<Point<Dist, D>> {
+    if D != 2 {
+        return points.to_vec();
+    }
+
+    let sorted = points
+        .iter()
+        .copied()
+        .sorted_by(|p, q| {
+            Dist::total_cmp(&p[0], &q[0]).then(Dist::total_cmp(&p[1], &q[1]))
+        })
+        .dedup()
+        .collect_vec();
+    if sorted.len() <= 2 {
+        return sorted;
+    }
+
+    let turns_left =
+        |a: &Point<Dist, D>, b: &Point<Dist, D>, c: &Point<Dist, D>| {
+            (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]) > 0.
+        };
+
+    // Lower hull from left to right, then upper hull from right to left,
+    // each ending at the first point of the other
+    let mut hull: Vec<Point<Dist, D>> = vec![];
+    for chain in [
+        Either::Left(sorted.iter()),
+        Either::Right(sorted.iter().rev()),
+    ] {
+        let start_len = hull.len();
+        for point in chain {
+            while hull.len() >= start_len + 2
+                && !turns_left(
+                    &hull[hull.len() - 2],
+                    &hull[hull.len() - 1],
+                    point,
+                )
+            {
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+    use crate::geom::metric::PointMetric;
+
+    #[test]
+    fn min_dist_matches_all_pairs() {
+        // Two interleaved zigzags, long enough to build several levels
+        let zigzag = |offset: Dist| {
+            (0..50)
+                .map(|idx| {
+                    let x = idx as Dist;
+                    point![x, offset + (x * 0.7).sin() * 3.]
+                })
+                .collect_vec()
+        };
+        let segments = |points: Vec<Point<Dist, 2>>| {
+            points
+                .into_iter()
+                .tuple_windows()
+                .map(|(p, q)| [p, q])
+                .collect()
+        };
+        let points_1 = zigzag(0.);
+        let points_2 = zigzag(7.2);
+        let bvh_1 = SegmentBvh::new(segments(points_1.clone()));
+        let bvh_2 = SegmentBvh::new(segments(points_2.clone()));
+
+        for metric in [PointMetric::Euclidean, PointMetric::Manhattan] {
+            let expected = Itertools::cartesian_product(
+                points_1.iter().tuple_windows(),
+                points_2.iter().tuple_windows(),
+            )
+            .map(|((p1, q1), (p2, q2))| {
+                metric.segment_dist(
+                    &LineSegment::from((*p1, *q1)),
+                    &LineSegment::from((*p2, *q2)),
+                )
+            })
+            .fold(Dist::INFINITY, Dist::min);
+
+            assert_relative_eq!(bvh_1.min_dist(&bvh_2, &metric), expected);
+        }
+    }
+
+    #[test]
+    fn square_hull() {
+        let hull = extreme_points(&[
+            point![0.0, 0.0],
+            point![1.0, 1.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+            point![1.0, 0.0],
+            point![0.0, 2.0],
+        ]);
+        assert_eq!(
+            hull,
+            vec![
+                point![0.0, 0.0],
+                point![2.0, 0.0],
+                point![2.0, 2.0],
+                point![0.0, 2.0],
+            ]
+        );
+    }
+}
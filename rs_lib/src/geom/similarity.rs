@@ -0,0 +1,248 @@
+use itertools::Itertools;
+use nalgebra::{
+    Complex, Point, Similarity2, Translation2, UnitComplex, Vector2,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::geom::curve::Curve;
+use crate::geom::dtw::{dtw, DtwOptions};
+use crate::geom::frechet::{frechet_dist, optimal_frechet_matching};
+use crate::geom::Dist;
+use crate::math::function::Function;
+use crate::traits::mix::Mix;
+
+const NUM_SAMPLES: usize = 64;
+const MAX_ITERATIONS: usize = 20;
+
+/// Cost under which the second curve is aligned onto the first.
+#[derive(Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityCost {
+    Frechet,
+    Dtw,
+}
+
+impl SimilarityCost {
+    fn eval(self, curves: [&Curve; 2]) -> Dist {
+        match self {
+            Self::Frechet => frechet_dist(curves),
+            Self::Dtw => {
+                dtw(curves, &DtwOptions::default())
+                    .expect("unconstrained DTW should admit a warping path")
+                    .cost
+            }
+        }
+    }
+
+    /// Returns pairs of corresponding points on the two curves under an
+    /// optimal matching for this cost.
+    fn correspondences(self, curves: [&Curve; 2]) -> Vec<[Point<Dist, 2>; 2]> {
+        let [curve_1, curve_2] = curves;
+        match self {
+            Self::Frechet => {
+                let matching = optimal_frechet_matching(curves);
+                let res = (curve_1.total_length() + curve_2.total_length())
+                    / NUM_SAMPLES as Dist;
+
+                // Sample the matching densely, since straight pieces of it
+                // pair up whole segments
+                matching
+                    .iter()
+                    .tuple_windows()
+                    .flat_map(|(&start, &end)| {
+                        let num_pieces =
+                            (((end - start).lp_norm(1) / res).ceil() as usize)
+                                .max(1);
+                        (0..num_pieces).map(move |piece_idx| {
+                            start.mix(
+                                end,
+                                piece_idx as Dist / num_pieces as Dist,
+                            )
+                        })
+                    })
+                    .chain(matching.last().copied())
+                    .map(|p| [curve_1.eval(p.x), curve_2.eval(p.y)])
+                    .collect()
+            }
+            Self::Dtw => {
+                let [points_1, points_2] = curves.map(|curve| curve.points());
+                dtw(curves, &DtwOptions::default())
+                    .expect("unconstrained DTW should admit a warping path")
+                    .path
+                    .into_iter()
+                    .map(|(i, j)| [points_1[i], points_2[j]])
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Similarity transform in a form that can be exchanged with JavaScript.
+#[derive(Serialize, Deserialize)]
+pub struct SimilarityTransform {
+    /// Counterclockwise rotation angle in radians
+    pub rotation: Dist,
+    pub scale: Dist,
+    pub translation: Vector2<Dist>,
+}
+
+impl From<&Similarity2<Dist>> for SimilarityTransform {
+    fn from(similarity: &Similarity2<Dist>) -> Self {
+        Self {
+            rotation: similarity.isometry.rotation.angle(),
+            scale: similarity.scaling(),
+            translation: similarity.isometry.translation.vector,
+        }
+    }
+}
+
+impl From<&SimilarityTransform> for Similarity2<Dist> {
+    fn from(transform: &SimilarityTransform) -> Self {
+        Similarity2::from_parts(
+            Translation2::from(transform.translation),
+            UnitComplex::new(transform.rotation),
+            transform.scale,
+        )
+    }
+}
+
+pub struct SimilarityAlignment {
+    /// Residual distance between the first curve and the transformed second
+    pub dist: Dist,
+    /// Transform of the second curve that attains `dist`
+    pub transform: Similarity2<Dist>,
+}
+
+/// Returns the similarity transform minimizing the sum of squared distances
+/// from the transformed second points of `pairs` to the first, in closed form
+/// by treating points as complex numbers.
+fn fit_similarity(pairs: &[[Point<Dist, 2>; 2]]) -> Option<Similarity2<Dist>> {
+    let centroid = |idx: usize| {
+        pairs
+            .iter()
+            .fold(Vector2::zeros(), |sum, pair| sum + pair[idx].coords)
+            / pairs.len() as Dist
+    };
+    let [target_centroid, source_centroid] = [centroid(0), centroid(1)];
+
+    let (numerator, denominator) = pairs.iter().fold(
+        (Complex::new(0., 0.), 0.),
+        |(numerator, denominator), [target, source]| {
+            let target = target.coords - target_centroid;
+            let source = source.coords - source_centroid;
+            let target = Complex::new(target.x, target.y);
+            let source = Complex::new(source.x, source.y);
+            (
+                numerator + source.conj() * target,
+                denominator + source.norm_sqr(),
+            )
+        },
+    );
+    if denominator == 0. {
+        return None;
+    }
+
+    let factor = numerator / denominator;
+    let scale = factor.norm_sqr().sqrt();
+    if scale == 0. {
+        return None;
+    }
+    let rotation = UnitComplex::from_complex(factor);
+    let translation = target_centroid - rotation * source_centroid * scale;
+
+    Some(Similarity2::from_parts(
+        Translation2::from(translation),
+        rotation,
+        scale,
+    ))
+}
+
+/// Finds a similarity transform (rotation, uniform scale and translation) of
+/// the second curve that minimizes its distance to the first under `cost`.
+///
+/// Starting from a fit of points at equal arc length fractions, the transform
+/// is refined by alternating between computing an optimal matching of the
+/// current curves and refitting the transform to the matched point pairs,
+/// until the cost stops improving.
+pub fn similarity_alignment(
+    curves: [&Curve; 2],
+    cost: SimilarityCost,
+) -> SimilarityAlignment {
+    let [curve_1, curve_2] = curves;
+
+    let initial_pairs = (0..=NUM_SAMPLES)
+        .map(|idx| {
+            let fraction = idx as Dist / NUM_SAMPLES as Dist;
+            [curve_1, curve_2]
+                .map(|curve| curve.eval(fraction * curve.total_length()))
+        })
+        .collect_vec();
+
+    let mut transform =
+        fit_similarity(&initial_pairs).unwrap_or_else(Similarity2::identity);
+    let mut transformed_curve = curve_2.transformed(&transform);
+    let mut dist = cost.eval([curve_1, &transformed_curve]);
+
+    for _ in 0..MAX_ITERATIONS {
+        let pairs = cost.correspondences([curve_1, &transformed_curve]);
+        let Some(step) = fit_similarity(&pairs) else {
+            break;
+        };
+
+        let new_transform = step * transform;
+        let new_transformed_curve = curve_2.transformed(&new_transform);
+        let new_dist = cost.eval([curve_1, &new_transformed_curve]);
+
+        if new_dist >= dist * (1. - 1e-4) {
+            if new_dist < dist {
+                (transform, dist) = (new_transform, new_dist);
+            }
+            break;
+        }
+        (transform, transformed_curve, dist) =
+            (new_transform, new_transformed_curve, new_dist);
+    }
+
+    SimilarityAlignment { dist, transform }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn rotated_scaled_copy() {
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 2.0],
+            point![3.0, 1.0],
+            point![4.0, 3.0],
+        ]);
+        let transform = Similarity2::from_parts(
+            Translation2::new(5.0, -2.0),
+            UnitComplex::new(0.5),
+            2.0,
+        );
+        let curve_2 = curve_1.transformed(&transform);
+
+        for cost in [SimilarityCost::Frechet, SimilarityCost::Dtw] {
+            let result = similarity_alignment([&curve_1, &curve_2], cost);
+            assert!(result.dist < 1e-3);
+
+            let aligned = curve_2.transformed(&result.transform);
+            assert_relative_eq!(
+                aligned.points()[3],
+                point![4.0, 3.0],
+                epsilon = 1e-3
+            );
+            assert_relative_eq!(
+                result.transform.isometry.translation.vector,
+                (transform.inverse()).isometry.translation.vector,
+                epsilon = 1e-3
+            );
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use nalgebra::{Point, Vector2};
+use serde::Serialize;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::frechet_dist;
+use crate::geom::Dist;
+
+#[derive(Serialize)]
+pub struct TranslationFrechet {
+    pub dist: Dist,
+    /// Translation of the second curve that attains `dist`, to be applied
+    /// with [`Curve::translated`]
+    pub translation: Vector2<Dist>,
+}
+
+/// Square of translations `center ± half_width` in both coordinates, ordered
+/// such that the square with the smallest lower bound on the distance comes
+/// first.
+struct Candidate {
+    lower_bound: Dist,
+    center: Vector2<Dist>,
+    half_width: Dist,
+    /// Distance at `center`
+    dist: Dist,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Dist::total_cmp(&other.lower_bound, &self.lower_bound)
+    }
+}
+
+impl Candidate {
+    /// Creates the square around `center` with distance `dist` at its center,
+    /// where `alignments` are translations that the distance can be no closer
+    /// to than it is large.
+    fn new(
+        center: Vector2<Dist>,
+        half_width: Dist,
+        dist: Dist,
+        alignments: [Vector2<Dist>; 2],
+    ) -> Self {
+        let alignment_bound = alignments
+            .iter()
+            .map(|alignment| {
+                (alignment - center)
+                    .abs()
+                    .map(|coord| (coord - half_width).max(0.))
+                    .norm()
+            })
+            .fold(0., Dist::max);
+
+        Self {
+            lower_bound: (dist - half_width * Dist::sqrt(2.))
+                .max(alignment_bound),
+            center,
+            half_width,
+            dist,
+        }
+    }
+}
+
+/// Minimizes the Fréchet distance over all translations of the second curve,
+/// up to a small tolerance relative to the size of the curves.
+///
+/// The distance is 1-Lipschitz in the translation, and at least the distance
+/// between the translated start points, as well as between the translated end
+/// points. Hence every translation improving on the best of a few aligning
+/// translations lies within that distance of the translation aligning the
+/// start points. A branch-and-bound search subdivides the square around it in
+/// order of the lower bounds of its parts, until no part can improve on the
+/// best distance by more than the tolerance.
+pub fn translation_frechet(curves: [&Curve; 2]) -> TranslationFrechet {
+    let [curve_1, curve_2] = curves;
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+
+    let dist_at = |translation: &Vector2<Dist>| {
+        frechet_dist([curve_1, &curve_2.translated(translation)])
+    };
+
+    let centroid = |points: &[Point<Dist, 2>]| {
+        points
+            .iter()
+            .fold(Vector2::zeros(), |sum, p| sum + p.coords)
+            / points.len() as Dist
+    };
+
+    let alignments = [
+        points_1.first().unwrap() - points_2.first().unwrap(),
+        points_1.last().unwrap() - points_2.last().unwrap(),
+    ];
+
+    let (mut translation, mut dist) = alignments
+        .into_iter()
+        .chain([centroid(points_1) - centroid(points_2)])
+        .map(|translation| (translation, dist_at(&translation)))
+        .min_by(|(_, dist_1), (_, dist_2)| Dist::total_cmp(dist_1, dist_2))
+        .unwrap();
+
+    let tolerance =
+        curve_1.total_length().max(curve_2.total_length()).max(dist) * 1e-3;
+    let mut queue = BinaryHeap::from([Candidate::new(
+        alignments[0],
+        dist,
+        dist_at(&alignments[0]),
+        alignments,
+    )]);
+
+    while let Some(candidate) = queue.pop() {
+        if candidate.lower_bound >= dist - tolerance {
+            break;
+        }
+
+        let half_width = candidate.half_width / 2.;
+        for offset in [[-1., -1.], [-1., 1.], [1., -1.], [1., 1.]] {
+            let center = candidate.center + Vector2::from(offset) * half_width;
+            let part = Candidate::new(
+                center,
+                half_width,
+                dist_at(&center),
+                alignments,
+            );
+            if part.dist < dist {
+                (translation, dist) = (center, part.dist);
+            }
+
+            queue.push(part);
+        }
+    }
+
+    TranslationFrechet { dist, translation }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn shifted_copy() {
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 2.0],
+            point![3.0, 1.0],
+        ]);
+        let curve_2 = curve_1.translated(&vector![3.0, -1.0]);
+
+        let result = translation_frechet([&curve_1, &curve_2]);
+        assert!(result.dist < 1e-3);
+        assert_relative_eq!(
+            result.translation,
+            vector![-3.0, 1.0],
+            epsilon = 1e-3
+        );
+
+        let aligned = curve_2.translated(&result.translation);
+        assert_relative_eq!(
+            aligned.points()[1],
+            point![1.0, 2.0],
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn shorter_segment() {
+        // None of the aligning translations is optimal: the shorter curve
+        // should be centered on the longer one
+        let curve_1 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![4.0, 0.0],
+        ]);
+        let curve_2 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+
+        let result = translation_frechet([&curve_1, &curve_2]);
+        assert_relative_eq!(result.dist, 1.0, epsilon = 1e-2);
+        assert_relative_eq!(
+            result.translation,
+            vector![1.0, 0.0],
+            epsilon = 1e-2
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use itertools::Itertools;
+
+use crate::geom::curve::Curve;
+use crate::geom::frechet::segments;
+use crate::geom::Dist;
+
+fn find(parents: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    // Path compression
+    let mut idx = idx;
+    while parents[idx] != root {
+        let next = parents[idx];
+        parents[idx] = root;
+        idx = next;
+    }
+
+    root
+}
+
+/// Computes the weak Fréchet distance between the two curves, i.e. the
+/// smallest `epsilon` for which `(0, 0)` and `(L1, L2)` lie in the same
+/// connected component of the sublevel set `{CurveDistFn <= epsilon}`.
+///
+/// The free space within each cell of the parameter space (the product of a
+/// segment of each curve) is convex, so two neighbouring cells are connected
+/// as soon as the free space on their shared boundary is non-empty, which
+/// happens at the distance between the boundary's vertex and segment. The
+/// result is then the bottleneck of the cheapest path through the cells.
+pub fn weak_frechet_dist<const D: usize>(curves: [&Curve<D>; 2]) -> Dist {
+    let [points_1, points_2] = curves.map(|curve| curve.points());
+    let [segments_1, segments_2] = curves.map(segments);
+    let (n, m) = (segments_1.len(), segments_2.len());
+
+    let endpoint_dist = Dist::max(
+        (points_1.first().unwrap() - points_2.first().unwrap()).norm(),
+        (points_1.last().unwrap() - points_2.last().unwrap()).norm(),
+    );
+
+    let cell_idx = |i: usize, j: usize| i * m + j;
+
+    // Shared boundaries between neighbouring cells, with the distance at which
+    // they become passable
+    let mut boundaries = Itertools::cartesian_product(0..n, 0..m)
+        .flat_map(|(i, j)| {
+            let right = (i + 1 < n).then(|| {
+                let dist =
+                    segments_2[j].point_dist_squared(&points_1[i + 1]).sqrt();
+                (dist, cell_idx(i, j), cell_idx(i + 1, j))
+            });
+            let top = (j + 1 < m).then(|| {
+                let dist =
+                    segments_1[i].point_dist_squared(&points_2[j + 1]).sqrt();
+                (dist, cell_idx(i, j), cell_idx(i, j + 1))
+            });
+            [right, top].into_iter().flatten()
+        })
+        .collect_vec();
+    boundaries.sort_by(|(dist_1, ..), (dist_2, ..)| dist_1.total_cmp(dist_2));
+
+    // Add boundaries in order of increasing distance, until the first and last
+    // cell are connected
+    let (start, end) = (cell_idx(0, 0), cell_idx(n - 1, m - 1));
+    let mut parents = (0..n * m).collect_vec();
+    let mut bottleneck_dist = 0.;
+
+    for (dist, cell_1, cell_2) in boundaries {
+        if find(&mut parents, start) == find(&mut parents, end) {
+            break;
+        }
+
+        let root_1 = find(&mut parents, cell_1);
+        let root_2 = find(&mut parents, cell_2);
+        parents[root_1] = root_2;
+        bottleneck_dist = dist;
+    }
+
+    endpoint_dist.max(bottleneck_dist)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use crate::geom::frechet::frechet_dist;
+
+    use super::*;
+
+    #[test]
+    fn backtracking() {
+        let curve_1 =
+            Curve::from_points(vec![point![0.0, 0.0], point![2.0, 0.0]]);
+        let curve_2 = Curve::from_points(vec![
+            point![0.0, 0.0],
+            point![1.5, 0.0],
+            point![0.5, 0.0],
+            point![2.0, 0.0],
+        ]);
+
+        // Unlike the (strong) Fréchet distance, the weak Fréchet distance
+        // allows backtracking along the first curve
+        assert_relative_eq!(weak_frechet_dist([&curve_1, &curve_2]), 0.0);
+        assert!(frechet_dist([&curve_1, &curve_2]) > 0.0);
+    }
+}
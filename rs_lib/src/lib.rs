@@ -1,15 +1,23 @@
-use std::iter;
+use std::cell::{OnceCell, Ref, RefCell};
 
 use itertools::Itertools;
-use nalgebra::{vector, Matrix4};
+use nalgebra::{point, vector, Matrix4, Point, Vector2};
 use ouroboros::self_referencing;
 use serde::Deserialize;
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlShader};
 
-use crate::geom::curve::Curve;
+use crate::geom::any_curve::{with_curve_pair, AnyCurve};
+use crate::geom::curve::{subdivide_lengths, Curve};
 use crate::geom::curve_dist_fn::CurveDistFn;
-use crate::geom::{Dist, JsCurve};
+use crate::geom::frechet::{
+    frechet_dist, optimal_frechet_matching, reachable_entries,
+};
+use crate::geom::free_space::FreeSpaceDiagram;
+use crate::geom::metric::PointMetric;
+use crate::geom::polygonal_domain::PolygonalDomain;
+use crate::geom::weak_frechet::weak_frechet_dist;
+use crate::geom::{Dist, IPointMetric, IPoints, IPolygons, JsCurve, JsCurveNd};
 use crate::math::function::Function;
 use crate::math::gradient::Gradient;
 use crate::plot::element_mesh::{ElementMesh, Vertex};
@@ -17,6 +25,8 @@ use crate::plot::isolines;
 use crate::plot::isolines::BuildIsolines;
 use crate::plot::layers::contour_lines::ContourLinesLayer;
 use crate::plot::layers::density::DensityLayer;
+use crate::plot::layers::free_space::FreeSpaceLayer;
+use crate::plot::layers::matching_path::MatchingPathLayer;
 use crate::traits::mix::Mix;
 
 mod geom;
@@ -36,41 +46,12 @@ const FLOATS_PER_POSITION: i32 = 2;
 const FLOATS_PER_VALUE: i32 = 1;
 const FLOATS_PER_VERTEX: i32 = FLOATS_PER_POSITION + FLOATS_PER_VALUE;
 
-fn subdivide_lengths(
-    lengths: &Vec<Dist>,
-    res: Dist,
-    [min, max]: [Dist; 2],
-) -> Vec<Dist> {
-    if lengths.is_empty() {
-        return vec![];
-    }
-
-    let lengths =
-        iter::once(*lengths.first().unwrap())
-            .chain(lengths.iter().tuple_windows::<(_, _)>().flat_map(
-                |(l1, l2)| {
-                    // Note: since `num_subdivisions` is 0 if both lengths are equal, this
-                    // effectively also deduplicates the lengths
-                    let num_subdivisions = ((l2 - l1) / res).ceil() as usize;
-                    (0..num_subdivisions).map(move |i| {
-                        let t = (i + 1) as Dist / (num_subdivisions as Dist);
-                        l1 * (1. - t) + l2 * t
-                    })
-                },
-            ))
-            .collect_vec();
-
-    let lo = lengths.partition_point(|&length| length <= min).max(1) - 1;
-    let hi = lengths
-        .partition_point(|&length| length <= max)
-        .min(lengths.len() - 1);
-    lengths[lo..=hi].to_vec()
-}
-
 #[wasm_bindgen(typescript_custom_section)]
 const TYPESCRIPT_CUSTOM_SECTION: &'static str = r#"
 export type IDrawOptions = {
     show_mesh: boolean;
+    show_matching_path: boolean;
+    free_space_epsilon?: number;
     x_bounds: [min: number, max: number];
     y_bounds: [min: number, max: number];
     x_scale: number;
@@ -90,15 +71,24 @@ extern "C" {
 #[derive(Deserialize)]
 struct DrawOptions {
     show_mesh: bool,
-    x_bounds: [f32; 2],
-    y_bounds: [f32; 2],
-    x_scale: f32,
-    y_scale: f32,
+    show_matching_path: bool,
+    /// Leash length up to which the free space is shaded, if at all
+    free_space_epsilon: Option<Dist>,
+    x_bounds: [Dist; 2],
+    y_bounds: [Dist; 2],
+    x_scale: Dist,
+    y_scale: Dist,
     draw_width: i32,
     draw_height: i32,
     device_pixel_ratio: f32,
 }
 
+struct PlotMesh {
+    element_mesh: ElementMesh<Dist>,
+    value_range: [Dist; 2],
+    isoline_thresholds: Vec<Dist>,
+}
+
 #[self_referencing]
 struct ContextWithLayers {
     context: WebGl2RenderingContext,
@@ -107,14 +97,38 @@ struct ContextWithLayers {
     #[covariant]
     density_layer: DensityLayer<'this>,
 
+    #[borrows(context)]
+    #[covariant]
+    free_space_layer: FreeSpaceLayer<'this>,
+
     #[borrows(context)]
     #[covariant]
     contour_lines_layer: ContourLinesLayer<'this>,
+
+    #[borrows(context)]
+    #[covariant]
+    matching_path_layer: MatchingPathLayer<'this>,
 }
 
+/// Lowest reachable points on the bottom and left boundary of each cell of a
+/// free-space diagram, as returned by [`Plotter::reachable_entries`].
+type ReachableEntries = Option<Vec<Vec<[Dist; 2]>>>;
+
 #[wasm_bindgen(getter_with_clone)]
 pub struct Plotter {
-    curves: [Curve; 2],
+    /// Curves of the same dimension
+    curves: [AnyCurve; 2],
+    /// Obstacles around which leash lengths are measured, if any, for planar
+    /// curves
+    domain: PolygonalDomain,
+    /// Metric in which straight leash lengths are measured
+    metric: PointMetric,
+    /// Matching realizing the Fréchet distance, computed when it is first
+    /// needed after the curves, obstacles or metric changed
+    matching_path: OnceCell<Vec<Point<Dist, 2>>>,
+    /// Epsilon of the free space drawn last and the reachable entries of its
+    /// cells, kept until the curves, obstacles, metric or epsilon change
+    reachable_entries: RefCell<Option<(Dist, ReachableEntries)>>,
     context_with_layers: ContextWithLayers,
 }
 
@@ -134,14 +148,22 @@ impl Plotter {
         let context_with_layers = ContextWithLayersTryBuilder {
             context,
             density_layer_builder: |context| DensityLayer::new(context),
+            free_space_layer_builder: |context| FreeSpaceLayer::new(context),
             contour_lines_layer_builder: |context| {
                 ContourLinesLayer::new(context)
             },
+            matching_path_layer_builder: |context| {
+                MatchingPathLayer::new(context)
+            },
         }
         .try_build()?;
 
         Ok(Self {
-            curves: [Curve::default(), Curve::default()],
+            curves: [AnyCurve::default(), AnyCurve::default()],
+            domain: PolygonalDomain::default(),
+            metric: PointMetric::default(),
+            matching_path: OnceCell::new(),
+            reachable_entries: RefCell::new(None),
             context_with_layers,
         })
     }
@@ -153,10 +175,10 @@ impl Plotter {
     fn _draw(&self, options: DrawOptions) {
         let DrawOptions {
             show_mesh,
+            show_matching_path,
+            free_space_epsilon,
             x_bounds,
             y_bounds,
-            x_scale,
-            y_scale,
             draw_width,
             draw_height,
             device_pixel_ratio,
@@ -165,12 +187,7 @@ impl Plotter {
 
         let context = self.context_with_layers.borrow_context();
 
-        context.viewport(
-            0,
-            0,
-            draw_width,
-            draw_height,
-        );
+        context.viewport(0, 0, draw_width, draw_height);
 
         if let Ok(range) = context
             .get_parameter(WebGl2RenderingContext::ALIASED_LINE_WIDTH_RANGE)
@@ -183,69 +200,15 @@ impl Plotter {
             context.line_width(line_width);
         }
 
-        let scale = vector![x_scale, y_scale];
-
-        // Build mesh
-        let res = 64.0; // max pixels per subdivision
-        let x_points = subdivide_lengths(
-            self.curves[0].cumulative_lengths(),
-            res / x_scale,
-            x_bounds,
-        );
-        let y_points = subdivide_lengths(
-            self.curves[1].cumulative_lengths(),
-            res / y_scale,
-            y_bounds,
-        );
-
-        if x_points.is_empty() || y_points.is_empty() {
+        let Some(PlotMesh {
+            element_mesh,
+            value_range: [min_value, max_value],
+            isoline_thresholds,
+        }) = self.build_mesh(&options)
+        else {
             return;
-        }
-
-        let curve_dist_fn =
-            CurveDistFn::new([&self.curves[0], &self.curves[1]]);
-        let gradient_fn = curve_dist_fn.gradient();
-
-        let min_value = curve_dist_fn.min_dist();
-        let max_value = curve_dist_fn.max_dist();
-
-        let mut element_mesh =
-            ElementMesh::from_points((&x_points, &y_points), &curve_dist_fn);
-
-        let num_isolines = 10;
-        let isoline_thresholds = (0..num_isolines)
-            .map(|w_idx| {
-                1. / ((num_isolines + 1) as Dist) * ((w_idx + 1) as Dist)
-            })
-            .map(|w| min_value + (max_value - min_value) * w)
-            .collect_vec();
-
-        let isoline_precision = 0.2; // how many pixels can isolines be off by
-
-        let should_refine_triangle = |triangle: [&Vertex<Dist>; 3]| -> bool {
-            isoline_thresholds.iter().any(|&threshold_value| {
-                isolines::analyze_triangle(triangle, threshold_value)
-                    .map(|[v0, v1]| {
-                        let should_refine_vertex = |v: Vertex<Dist>| {
-                            let gradient_magnitude = gradient_fn
-                                .eval(v.point)
-                                .component_div(&scale)
-                                .magnitude();
-                            let true_value = curve_dist_fn.eval(v.point);
-                            let error = (v.value - true_value).abs();
-                            error > isoline_precision * gradient_magnitude
-                        };
-
-                        should_refine_vertex(v0)
-                            || should_refine_vertex(v1)
-                            || should_refine_vertex(v0.mix(v1, 0.5))
-                    })
-                    .unwrap_or(false)
-            })
         };
 
-        element_mesh.refine(&curve_dist_fn, should_refine_triangle);
-
         // Build isoline data
         let mut isoline_vertex_data: Vec<Vertex<Dist>> = isoline_thresholds
             .iter()
@@ -269,8 +232,12 @@ impl Plotter {
         }
 
         let density_layer = self.context_with_layers.borrow_density_layer();
+        let free_space_layer =
+            self.context_with_layers.borrow_free_space_layer();
         let contour_lines_layer =
             self.context_with_layers.borrow_contour_lines_layer();
+        let matching_path_layer =
+            self.context_with_layers.borrow_matching_path_layer();
 
         // TODO: Make this configurable?
         let sharp_gradient = true;
@@ -281,7 +248,7 @@ impl Plotter {
                 .update_gradient_sharp(
                     &context,
                     color_gradient,
-                    num_isolines + 1,
+                    isoline_thresholds.len() + 1,
                 )
                 .unwrap();
         } else {
@@ -303,20 +270,364 @@ impl Plotter {
             .append_translation(&vector![-1.0, -1.0, 0.0]);
 
         density_layer.update_transform(&context, m);
+        free_space_layer.update_transform(context, m);
         contour_lines_layer.update_transform(&context, m);
+        matching_path_layer.update_transform(context, m);
 
         // Draw
         context.clear_color(0.0, 0.0, 0.0, 1.0);
         context.clear(WebGl2RenderingContext::COLOR_BUFFER_BIT);
 
         density_layer.draw(&context, &element_mesh).unwrap();
+        if let Some(epsilon) = free_space_epsilon {
+            let entries = self.cached_reachable_entries(epsilon);
+            let [params_1, params_2] = [&self.curves[0], &self.curves[1]]
+                .map(AnyCurve::cumulative_params);
+            // Without reachable entries, only the free space itself is shaded
+            let reachable_from = entries.as_ref().map(|entries| {
+                move |point: Point<Dist, 2>| {
+                    let (n, m) = (entries.len(), entries[0].len());
+                    let i = params_1.partition_point(|&param| param <= point.x);
+                    let j = params_2.partition_point(|&param| param <= point.y);
+                    entries[i.clamp(1, n) - 1][j.clamp(1, m) - 1]
+                }
+            });
+            free_space_layer
+                .draw(context, &element_mesh, epsilon, reachable_from)
+                .unwrap();
+        }
         contour_lines_layer
             .draw(&context, isoline_vertex_data)
             .unwrap();
+        if show_matching_path {
+            matching_path_layer
+                .draw(context, self.cached_matching_path())
+                .unwrap();
+        }
+    }
+
+    /// Builds the refined mesh of the distance function over the visible part
+    /// of the parameter space, or `None` if nothing is visible.
+    fn build_mesh(&self, options: &DrawOptions) -> Option<PlotMesh> {
+        let DrawOptions {
+            x_bounds,
+            y_bounds,
+            x_scale,
+            y_scale,
+            free_space_epsilon,
+            ..
+        } = *options;
+
+        let scale = vector![x_scale, y_scale];
+
+        // Build mesh
+        let res = 64.0; // max pixels per subdivision
+        let x_points = subdivide_lengths(
+            self.curves[0].cumulative_params(),
+            res / x_scale,
+            x_bounds,
+        );
+        let y_points = subdivide_lengths(
+            self.curves[1].cumulative_params(),
+            res / y_scale,
+            y_bounds,
+        );
+
+        if x_points.is_empty() || y_points.is_empty() {
+            return None;
+        }
+
+        let points = (&x_points, &y_points);
+        match &self.curves {
+            [AnyCurve::D2(curve_1), AnyCurve::D2(curve_2)]
+                if !self.domain.is_empty() =>
+            {
+                let curve_dist_fn =
+                    CurveDistFn::geodesic([curve_1, curve_2], &self.domain);
+                Some(Self::build_mesh_for(
+                    &curve_dist_fn,
+                    points,
+                    scale,
+                    free_space_epsilon,
+                ))
+            }
+            curves => with_curve_pair!(curves, curves => {
+                let curve_dist_fn =
+                    CurveDistFn::new(curves).with_metric(&self.metric);
+                Self::build_mesh_for(
+                    &curve_dist_fn,
+                    points,
+                    scale,
+                    free_space_epsilon,
+                )
+            }),
+        }
+    }
+
+    /// Builds the refined mesh of `curve_dist_fn` on the grid spanned by the
+    /// lengths in `points`, refining triangles until the isolines, and the
+    /// boundary of the free space for `free_space_epsilon` if given, are
+    /// accurate to a fraction of a pixel at the given `scale`.
+    fn build_mesh_for<const D: usize>(
+        curve_dist_fn: &CurveDistFn<D>,
+        (x_points, y_points): (&Vec<Dist>, &Vec<Dist>),
+        scale: Vector2<Dist>,
+        free_space_epsilon: Option<Dist>,
+    ) -> PlotMesh {
+        let gradient_fn = curve_dist_fn.gradient();
+
+        let min_value = curve_dist_fn.min_dist();
+        let max_value = curve_dist_fn.max_dist();
+
+        let mut element_mesh =
+            ElementMesh::from_points((x_points, y_points), curve_dist_fn);
+
+        let num_isolines = 10;
+        let isoline_thresholds = (0..num_isolines)
+            .map(|w_idx| {
+                1. / ((num_isolines + 1) as Dist) * ((w_idx + 1) as Dist)
+            })
+            .map(|w| min_value + (max_value - min_value) * w)
+            .collect_vec();
+
+        let isoline_precision = 0.2; // how many pixels can isolines be off by
+
+        let refine_thresholds = isoline_thresholds
+            .iter()
+            .copied()
+            .chain(free_space_epsilon)
+            .collect_vec();
+
+        let should_refine_triangle = |triangle: [&Vertex<Dist>; 3]| -> bool {
+            refine_thresholds.iter().any(|&threshold_value| {
+                isolines::analyze_triangle(triangle, threshold_value)
+                    .map(|[v0, v1]| {
+                        let should_refine_vertex = |v: Vertex<Dist>| {
+                            let gradient_magnitude = gradient_fn
+                                .eval(v.point)
+                                .component_div(&scale)
+                                .magnitude();
+                            let true_value = curve_dist_fn.eval(v.point);
+                            let error = (v.value - true_value).abs();
+                            error > isoline_precision * gradient_magnitude
+                        };
+
+                        should_refine_vertex(v0)
+                            || should_refine_vertex(v1)
+                            || should_refine_vertex(v0.mix(v1, 0.5))
+                    })
+                    .unwrap_or(false)
+            })
+        };
+
+        element_mesh.refine(curve_dist_fn, should_refine_triangle);
+
+        PlotMesh {
+            element_mesh,
+            value_range: [min_value, max_value],
+            isoline_thresholds,
+        }
     }
 
     pub fn update_curves(&mut self, curve_1: &JsCurve, curve_2: &JsCurve) {
-        self.curves = [curve_1.clone().into(), curve_2.clone().into()];
+        self.set_curves(
+            [curve_1, curve_2].map(|curve| Curve::from(curve.clone()).into()),
+        );
+    }
+
+    /// Like [`Self::update_curves`], for curves in any dimension. Fails if
+    /// the curves have different dimensions.
+    pub fn update_curves_nd(
+        &mut self,
+        curve_1: &JsCurveNd,
+        curve_2: &JsCurveNd,
+    ) -> Result<(), JsValue> {
+        let curves = [curve_1, curve_2].map(|curve| curve.clone().into());
+        let [dimension_1, dimension_2] =
+            [&curves[0], &curves[1]].map(AnyCurve::dimension);
+        if dimension_1 != dimension_2 {
+            return Err(JsValue::from_str(&format!(
+                "curves should have the same dimension, got {dimension_1} and {dimension_2}"
+            )));
+        }
+
+        self.set_curves(curves);
+        Ok(())
+    }
+
+    fn set_curves(&mut self, curves: [AnyCurve; 2]) {
+        let curves_changed =
+            self.curves.iter().zip(&curves).any(|(old, new)| {
+                !old.same_points(new)
+                    || old.cumulative_params() != new.cumulative_params()
+            });
+
+        self.curves = curves;
+
+        // Only drop the matching when the curves actually changed, since this
+        // is called before every draw
+        if curves_changed {
+            self.matching_path = OnceCell::new();
+            *self.reachable_entries.get_mut() = None;
+        }
+    }
+
+    /// Returns the matching realizing the Fréchet distance between the
+    /// current curves in parameter space, computing it if anything it depends
+    /// on changed since it was last needed. The matching is empty unless
+    /// leash lengths are straight Euclidean distances, for which it is
+    /// computed.
+    fn cached_matching_path(&self) -> &Vec<Point<Dist, 2>> {
+        self.matching_path.get_or_init(|| {
+            if self.has_empty_curve() || !self.has_euclidean_leashes() {
+                return vec![];
+            }
+
+            with_curve_pair!(&self.curves, curves => {
+                let [curve_1, curve_2] = curves;
+                optimal_frechet_matching(curves)
+                    .into_iter()
+                    .map(|point| {
+                        point![
+                            curve_1.length_to_param(point.x),
+                            curve_2.length_to_param(point.y)
+                        ]
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+        })
+    }
+
+    fn has_empty_curve(&self) -> bool {
+        self.curves.iter().any(AnyCurve::is_empty)
+    }
+
+    /// Returns whether leash lengths are straight Euclidean distances, which
+    /// the matching path and the free-space diagram assume.
+    fn has_euclidean_leashes(&self) -> bool {
+        self.domain.is_empty() && matches!(self.metric, PointMetric::Euclidean)
+    }
+
+    /// Returns the lowest points on the bottom and left boundary of each cell
+    /// of the free-space diagram for `epsilon` that are reachable from the
+    /// origin, as parameters along the first and second curve, or infinity
+    /// if there are none. Returns `None` unless leash lengths are straight
+    /// Euclidean distances.
+    fn reachable_entries(&self, epsilon: Dist) -> ReachableEntries {
+        if self.has_empty_curve() || !self.has_euclidean_leashes() {
+            return None;
+        }
+
+        with_curve_pair!(&self.curves, curves => {
+            let free_space = FreeSpaceDiagram::new(curves, epsilon);
+            let entries = reachable_entries(&free_space);
+            entries
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    column
+                        .into_iter()
+                        .enumerate()
+                        .map(|(j, [bottom, left])| {
+                            [(0, i, bottom), (1, j, left)].map(
+                                |(idx, segment_idx, t)| {
+                                    t.map_or(Dist::INFINITY, |t| {
+                                        curves[idx]
+                                            .segment_param_to_param(segment_idx, t)
+                                    })
+                                },
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    /// Returns [`Self::reachable_entries`] for `epsilon`, recomputing them
+    /// only if anything they depend on changed since they were last needed.
+    fn cached_reachable_entries(
+        &self,
+        epsilon: Dist,
+    ) -> Ref<'_, ReachableEntries> {
+        let is_cached = matches!(
+            *self.reachable_entries.borrow(),
+            Some((cached_epsilon, _)) if cached_epsilon == epsilon
+        );
+        if !is_cached {
+            *self.reachable_entries.borrow_mut() =
+                Some((epsilon, self.reachable_entries(epsilon)));
+        }
+
+        Ref::map(self.reachable_entries.borrow(), |cache| {
+            &cache.as_ref().unwrap().1
+        })
+    }
+
+    /// Sets the polygonal obstacles around which the plotted leash lengths are
+    /// measured. Without obstacles, straight-line distances are plotted.
+    pub fn set_obstacles(&mut self, obstacles: IPolygons) {
+        self.domain = PolygonalDomain::from_js(obstacles);
+        self.matching_path = OnceCell::new();
+        *self.reachable_entries.get_mut() = None;
+    }
+
+    /// Sets the metric in which the plotted leash lengths are measured, unless
+    /// there are obstacles.
+    pub fn set_metric(&mut self, metric: IPointMetric) {
+        self.metric = serde_wasm_bindgen::from_value(metric.into()).unwrap();
+        self.matching_path = OnceCell::new();
+        *self.reachable_entries.get_mut() = None;
+    }
+
+    /// Monotone matching realizing the Fréchet distance between the current
+    /// curves, as a polyline in parameter space, or empty unless leash
+    /// lengths are straight Euclidean distances. It is computed on first
+    /// access after the curves changed, so only read it when it is shown.
+    #[wasm_bindgen(getter)]
+    pub fn matching_path(&self) -> IPoints {
+        serde_wasm_bindgen::to_value(self.cached_matching_path())
+            .unwrap()
+            .into()
+    }
+
+    /// Returns the Fréchet distance between the current curves, or `undefined`
+    /// if either of them is empty.
+    pub fn frechet_dist(&self) -> Option<Dist> {
+        if self.has_empty_curve() {
+            return None;
+        }
+        with_curve_pair!(&self.curves, curves => frechet_dist(curves))
+    }
+
+    /// Returns the weak Fréchet distance between the current curves, or
+    /// `undefined` if either of them is empty.
+    pub fn weak_frechet_dist(&self) -> Option<Dist> {
+        if self.has_empty_curve() {
+            return None;
+        }
+        with_curve_pair!(&self.curves, curves => weak_frechet_dist(curves))
+    }
+
+    /// Debug variant of the weak Fréchet decision procedure, which checks
+    /// whether `(0, 0)` and `(L1, L2)` are connected in the sublevel set
+    /// `{d <= epsilon}` of the refined mesh that `draw` would render with the
+    /// same options. Returns `undefined` if either corner is not visible.
+    pub fn weak_frechet_decide_on_mesh(
+        &self,
+        epsilon: Dist,
+        options: IDrawOptions,
+    ) -> Option<bool> {
+        let options: DrawOptions =
+            serde_wasm_bindgen::from_value(options.into()).unwrap();
+        let PlotMesh { element_mesh, .. } = self.build_mesh(&options)?;
+
+        element_mesh.sublevel_set_connects(
+            point![0., 0.],
+            point![self.curves[0].total_param(), self.curves[1].total_param()],
+            epsilon,
+        )
     }
 }
 
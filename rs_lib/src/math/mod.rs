@@ -1,4 +1,5 @@
 pub mod function;
 pub mod gradient;
 pub mod partial_derivative;
+pub mod path_integral;
 pub mod scalar_field;
@@ -0,0 +1,86 @@
+use nalgebra::{Point, RealField};
+
+use crate::math::function::Function;
+
+pub trait PathIntegral<'f, T: RealField, const D: usize>:
+    Function<'f, Point<T, D>, Output = T>
+{
+    /// Integrates the function along the polyline through `path` with respect
+    /// to L1 arc length, using Simpson's rule on pieces of length at most
+    /// `max_step`. With this length element, every monotone path between two
+    /// points has the same total length.
+    fn path_integral(&'f self, path: &[Point<T, D>], max_step: T) -> T;
+}
+
+impl<'f, T: RealField + Copy, const D: usize, F> PathIntegral<'f, T, D> for F
+where
+    F: Function<'f, Point<T, D>, Output = T>,
+{
+    fn path_integral(&'f self, path: &[Point<T, D>], max_step: T) -> T {
+        let two = T::from_subset(&2.);
+        let four = T::from_subset(&4.);
+        let six = T::from_subset(&6.);
+
+        path.windows(2)
+            .map(|segment| {
+                let [start, end] = [segment[0], segment[1]];
+                let length = (end - start).lp_norm(1);
+                if length == T::zero() {
+                    return T::zero();
+                }
+
+                let num_pieces = (length / max_step).ceil().max(T::one());
+                let step = (end - start) / num_pieces;
+                let piece_length = length / num_pieces;
+
+                let num_pieces: f64 = num_pieces.to_subset_unchecked();
+                (0..num_pieces as usize)
+                    .map(|piece_idx| {
+                        let piece_start =
+                            start + step * T::from_subset(&(piece_idx as f64));
+                        let value_start = self.eval(piece_start);
+                        let value_mid = self.eval(piece_start + step / two);
+                        let value_end = self.eval(piece_start + step);
+                        (value_start + value_mid * four + value_end)
+                            * piece_length
+                            / six
+                    })
+                    .fold(T::zero(), |sum, integral| sum + integral)
+            })
+            .fold(T::zero(), |sum, integral| sum + integral)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn monotone_paths_have_equal_length() {
+        let one = |_: Point<f64, 2>| 1.;
+        let diagonal = [point![0., 0.], point![2., 1.]];
+        let staircase = [point![0., 0.], point![2., 0.], point![2., 1.]];
+
+        assert_relative_eq!(
+            one.path_integral(&diagonal, 0.1),
+            3.,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            one.path_integral(&staircase, 0.1),
+            3.,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn quadratic_is_exact() {
+        let square = |p: Point<f64, 2>| p.x * p.x;
+        let path = [point![0., 0.], point![3., 0.]];
+
+        assert_relative_eq!(square.path_integral(&path, 10.), 9.);
+    }
+}
@@ -3,10 +3,12 @@ use nalgebra::{Point, RealField};
 use crate::math::function::Function;
 use crate::math::gradient::Gradient;
 use crate::math::partial_derivative::PartialDerivative;
+use crate::math::path_integral::PathIntegral;
 
 pub trait ScalarField<'f, T: RealField, const D: usize>:
     Function<'f, Point<T, D>, Output = T>
     + PartialDerivative<'f, T, D>
     + Gradient<'f, T, D>
+    + PathIntegral<'f, T, D>
 {
 }
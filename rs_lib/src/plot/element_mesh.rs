@@ -15,15 +15,33 @@ pub struct Vertex<Value> {
     pub value: Value,
 }
 
-unsafe impl Zeroable for Vertex<Dist> {}
-unsafe impl Pod for Vertex<Dist> {}
+/// Vertex as uploaded to the GPU, which only handles single precision
+/// regardless of the precision of [`Dist`].
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct GpuVertex {
+    pub point: [f32; 2],
+    pub value: f32,
+}
 
-unsafe impl webgl::vertex::Vertex for Vertex<Dist> {
+unsafe impl Zeroable for GpuVertex {}
+unsafe impl Pod for GpuVertex {}
+
+unsafe impl webgl::vertex::Vertex for GpuVertex {
     fn build_bindings() -> VertexFormat {
         todo!()
     }
 }
 
+impl From<&Vertex<Dist>> for GpuVertex {
+    fn from(vertex: &Vertex<Dist>) -> Self {
+        Self {
+            point: vertex.point.coords.cast::<f32>().into(),
+            value: nalgebra::convert(vertex.value),
+        }
+    }
+}
+
 impl<Weight, Value> Mix<Weight> for Vertex<Value>
 where
     Weight: Copy,
@@ -397,10 +415,73 @@ impl<Value> ElementMesh<Value> {
     pub fn vertices(&self) -> &Vec<Vertex<Value>> {
         &self.vertices
     }
+
+    /// Returns whether the vertices at `from` and `to` lie in the same
+    /// connected component of the piecewise linear sublevel set
+    /// `{value <= threshold}`, or `None` if either point is not a vertex of the
+    /// mesh.
+    pub fn sublevel_set_connects(
+        &self,
+        from: Point<Dist, 2>,
+        to: Point<Dist, 2>,
+        threshold: Value,
+    ) -> Option<bool>
+    where
+        Value: PartialOrd,
+    {
+        let find_vertex = |point: Point<Dist, 2>| {
+            self.vertices
+                .iter()
+                .position(|vertex| vertex.point == point)
+        };
+        let from_idx = find_vertex(from)?;
+        let to_idx = find_vertex(to)?;
+
+        // The sublevel set of a linear function on a triangle is convex, so the
+        // vertices below the threshold are connected iff they are connected by
+        // edges that lie entirely below the threshold.
+        let is_below = |idx: usize| self.vertices[idx].value <= threshold;
+
+        let mut neighbours = vec![vec![]; self.vertices.len()];
+        for triangle in &self.triangles {
+            for edge_idx in 0..3 {
+                let [v1, v2] = triangle.edge(edge_idx);
+                if is_below(v1) && is_below(v2) {
+                    neighbours[v1].push(v2);
+                    neighbours[v2].push(v1);
+                }
+            }
+        }
+
+        if !is_below(from_idx) {
+            return Some(false);
+        }
+
+        let mut visited = vec![false; self.vertices.len()];
+        let mut queue = VecDeque::from([from_idx]);
+        visited[from_idx] = true;
+
+        while let Some(vertex_idx) = queue.pop_front() {
+            if vertex_idx == to_idx {
+                return Some(true);
+            }
+            for &neighbour_idx in &neighbours[vertex_idx] {
+                if !visited[neighbour_idx] {
+                    visited[neighbour_idx] = true;
+                    queue.push_back(neighbour_idx);
+                }
+            }
+        }
+
+        Some(false)
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use nalgebra::{point, Point};
+
+    use crate::geom::Dist;
     use crate::plot::element_mesh::ElementMesh;
 
     #[test]
@@ -409,4 +490,22 @@ mod test {
         let y_points = vec![0., 1., 2.];
         ElementMesh::from_points((&x_points, &y_points), &|_| 0.);
     }
+
+    #[test]
+    fn sublevel_set_connects() {
+        let x_points = vec![0., 1., 2.];
+        let y_points = vec![0., 1., 2.];
+        let mesh =
+            ElementMesh::from_points((&x_points, &y_points), &|p: Point<
+                Dist,
+                2,
+            >| {
+                p.x
+            });
+
+        let connects = |to| mesh.sublevel_set_connects(point![0., 0.], to, 0.5);
+        assert_eq!(connects(point![0., 2.]), Some(true));
+        assert_eq!(connects(point![2., 2.]), Some(false));
+        assert_eq!(connects(point![0.5, 0.5]), None);
+    }
 }
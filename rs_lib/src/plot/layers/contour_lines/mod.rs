@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use nalgebra::Matrix4;
 use web_sys::{
     WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation,
@@ -5,7 +6,8 @@ use web_sys::{
 };
 
 use crate::geom::Dist;
-use crate::plot::element_mesh::Vertex;
+use crate::plot::element_mesh::{GpuVertex, Vertex};
+use crate::plot::layers::upload_transform;
 use crate::webgl::buffer::{Buffer, BufferTarget, BufferUsage};
 use crate::webgl::vertex_buffer::VertexBuffer;
 use crate::{
@@ -17,7 +19,7 @@ pub struct ContourLinesLayer<'a> {
     program: WebGlProgram,
     u_transform: WebGlUniformLocation,
     vao: WebGlVertexArrayObject,
-    vertex_buffer: VertexBuffer<'a, Vertex<Dist>>,
+    vertex_buffer: VertexBuffer<'a, GpuVertex>,
 }
 
 impl<'a> ContourLinesLayer<'a> {
@@ -46,7 +48,7 @@ impl<'a> ContourLinesLayer<'a> {
             .ok_or("Failed to get uniform location")?;
 
         // Create buffers
-        let vertex_buffer: VertexBuffer<Vertex<Dist>> = Buffer::new(
+        let vertex_buffer: VertexBuffer<GpuVertex> = Buffer::new(
             context,
             BufferTarget::ArrayBuffer,
             BufferUsage::StaticDraw,
@@ -89,12 +91,7 @@ impl<'a> ContourLinesLayer<'a> {
         context: &WebGl2RenderingContext,
         mat: Matrix4<Dist>,
     ) {
-        context.use_program(Some(&self.program));
-        context.uniform_matrix4fv_with_f32_array(
-            Some(&self.u_transform),
-            false,
-            mat.transpose().data.as_slice(),
-        );
+        upload_transform(context, &self.program, &self.u_transform, mat);
     }
 
     pub fn draw(
@@ -104,6 +101,7 @@ impl<'a> ContourLinesLayer<'a> {
     ) -> Result<(), String> {
         context.use_program(Some(&self.program));
 
+        let vertex_data = vertex_data.iter().map(GpuVertex::from).collect_vec();
         self.vertex_buffer.write(&vertex_data);
 
         context.bind_vertex_array(Some(&self.vao));
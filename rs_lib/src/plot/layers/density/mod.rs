@@ -7,7 +7,8 @@ use web_sys::{
 };
 
 use crate::geom::Dist;
-use crate::plot::element_mesh::{ElementMesh, Vertex};
+use crate::plot::element_mesh::{ElementMesh, GpuVertex};
+use crate::plot::layers::upload_transform;
 use crate::webgl::buffer::{Buffer, BufferTarget, BufferUsage};
 use crate::webgl::index_buffer::IndexBuffer;
 use crate::webgl::vertex_buffer::VertexBuffer;
@@ -25,7 +26,7 @@ pub struct DensityLayer<'a> {
     gradient_texture: WebGlTexture,
 
     vao: WebGlVertexArrayObject,
-    vertex_buffer: VertexBuffer<'a, Vertex<Dist>>,
+    vertex_buffer: VertexBuffer<'a, GpuVertex>,
     index_buffer: IndexBuffer<'a, u32>,
 }
 
@@ -63,7 +64,7 @@ impl<'a> DensityLayer<'a> {
             .ok_or("Failed to get uniform location")?;
 
         // Create buffers
-        let vertex_buffer: VertexBuffer<Vertex<Dist>> = Buffer::new(
+        let vertex_buffer: VertexBuffer<GpuVertex> = Buffer::new(
             context,
             BufferTarget::ArrayBuffer,
             BufferUsage::StaticDraw,
@@ -134,7 +135,8 @@ impl<'a> DensityLayer<'a> {
         range: [Dist; 2],
     ) {
         context.use_program(Some(&self.program));
-        context.uniform2f(Some(&self.u_value_range), range[0], range[1]);
+        let [min, max] = range.map(nalgebra::convert::<Dist, f32>);
+        context.uniform2f(Some(&self.u_value_range), min, max);
     }
 
     pub fn update_transform(
@@ -142,12 +144,7 @@ impl<'a> DensityLayer<'a> {
         context: &WebGl2RenderingContext,
         mat: Matrix4<Dist>,
     ) {
-        context.use_program(Some(&self.program));
-        context.uniform_matrix4fv_with_f32_array(
-            Some(&self.u_transform),
-            false,
-            mat.transpose().data.as_slice(),
-        );
+        upload_transform(context, &self.program, &self.u_transform, mat);
     }
 
     pub fn update_gradient_smooth(
@@ -231,7 +228,8 @@ impl<'a> DensityLayer<'a> {
         context.use_program(Some(&self.program));
 
         // Build vertex data
-        let vertex_data = mesh.vertices();
+        let vertex_data =
+            mesh.vertices().iter().map(GpuVertex::from).collect_vec();
         let index_data: Vec<u32> = mesh
             .iter_triangle_elements()
             .flatten()
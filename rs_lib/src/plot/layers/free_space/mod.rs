@@ -0,0 +1,206 @@
+use bytemuck::{Pod, Zeroable};
+use itertools::Itertools;
+use nalgebra::{Matrix4, Point, Vector2};
+use web_sys::{
+    WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation,
+    WebGlVertexArrayObject,
+};
+
+use crate::geom::Dist;
+use crate::plot::element_mesh::ElementMesh;
+use crate::plot::layers::upload_transform;
+use crate::webgl::buffer::{Buffer, BufferTarget, BufferUsage};
+use crate::{
+    compile_shader, link_program, BYTES_PER_FLOAT, FLOATS_PER_POSITION,
+    FLOATS_PER_VALUE,
+};
+
+const FLOATS_PER_REACHABLE_FROM: i32 = 2;
+const FLOATS_PER_FREE_SPACE_VERTEX: i32 =
+    FLOATS_PER_POSITION + FLOATS_PER_VALUE + FLOATS_PER_REACHABLE_FROM;
+
+/// Vertex of a triangle in the free space, which also carries the lowest
+/// points from which the free space in its cell is reachable, as described by
+/// [`FreeSpaceLayer::draw`]. Its attributes are bound in
+/// [`FreeSpaceLayer::new`].
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+struct FreeSpaceVertex {
+    point: [f32; 2],
+    value: f32,
+    reachable_from: [f32; 2],
+}
+
+unsafe impl Zeroable for FreeSpaceVertex {}
+unsafe impl Pod for FreeSpaceVertex {}
+
+/// Shades the free space `{d <= epsilon}`, and in a different color the part
+/// of it that is reachable from `(0, 0)` by monotone paths.
+pub struct FreeSpaceLayer<'a> {
+    program: WebGlProgram,
+
+    u_transform: WebGlUniformLocation,
+    u_epsilon: WebGlUniformLocation,
+    u_show_reachable: WebGlUniformLocation,
+
+    vao: WebGlVertexArrayObject,
+    vertex_buffer: Buffer<'a, FreeSpaceVertex>,
+}
+
+impl<'a> FreeSpaceLayer<'a> {
+    pub fn new(context: &'a WebGl2RenderingContext) -> Result<Self, String> {
+        // Compiler shaders
+        let vert_shader = compile_shader(
+            context,
+            WebGl2RenderingContext::VERTEX_SHADER,
+            include_str!("shader.vert"),
+        )?;
+        let frag_shader = compile_shader(
+            context,
+            WebGl2RenderingContext::FRAGMENT_SHADER,
+            include_str!("shader.frag"),
+        )?;
+
+        // Create & link program
+        let program = link_program(context, &vert_shader, &frag_shader)?;
+
+        // Get attributes and uniforms
+        let a_position =
+            context.get_attrib_location(&program, "a_position") as u32;
+        let a_value = context.get_attrib_location(&program, "a_value") as u32;
+        let a_reachable_from =
+            context.get_attrib_location(&program, "a_reachable_from") as u32;
+
+        let u_transform = context
+            .get_uniform_location(&program, "u_transform")
+            .ok_or("Failed to get uniform location")?;
+        let u_epsilon = context
+            .get_uniform_location(&program, "u_epsilon")
+            .ok_or("Failed to get uniform location")?;
+        let u_show_reachable = context
+            .get_uniform_location(&program, "u_show_reachable")
+            .ok_or("Failed to get uniform location")?;
+
+        // Create buffers
+        let vertex_buffer: Buffer<FreeSpaceVertex> = Buffer::new(
+            context,
+            BufferTarget::ArrayBuffer,
+            BufferUsage::StaticDraw,
+        )
+        .map_err(|error| format!("{error:?}"))?;
+
+        // Setup vertex array object
+        let vao = context
+            .create_vertex_array()
+            .ok_or("Failed to create vertex array object")?;
+
+        context.bind_vertex_array(Some(&vao));
+
+        vertex_buffer.bind();
+
+        for (location, size, offset) in [
+            (a_position, FLOATS_PER_POSITION, 0),
+            (a_value, FLOATS_PER_VALUE, FLOATS_PER_POSITION),
+            (
+                a_reachable_from,
+                FLOATS_PER_REACHABLE_FROM,
+                FLOATS_PER_POSITION + FLOATS_PER_VALUE,
+            ),
+        ] {
+            context.enable_vertex_attrib_array(location);
+            context.vertex_attrib_pointer_with_i32(
+                location,
+                size,
+                WebGl2RenderingContext::FLOAT,
+                false,
+                FLOATS_PER_FREE_SPACE_VERTEX * BYTES_PER_FLOAT,
+                offset * BYTES_PER_FLOAT,
+            );
+        }
+
+        context.bind_vertex_array(None);
+
+        Ok(Self {
+            program,
+
+            u_transform,
+            u_epsilon,
+            u_show_reachable,
+
+            vao,
+            vertex_buffer,
+        })
+    }
+
+    pub fn update_transform(
+        &self,
+        context: &WebGl2RenderingContext,
+        mat: Matrix4<Dist>,
+    ) {
+        upload_transform(context, &self.program, &self.u_transform, mat);
+    }
+
+    /// Draws the free space of `mesh` for the given `epsilon`.
+    ///
+    /// A point in the free space is shaded as reachable if it lies right of or
+    /// above the corresponding coordinate returned by `reachable_from` for
+    /// the centroid of its triangle, which should be infinite where nothing
+    /// is reachable. This requires every triangle to lie within a single cell
+    /// of the free-space diagram. Without `reachable_from`, e.g. because
+    /// reachability is unknown for the plotted distances, the free space is
+    /// shaded in a third color that makes no claim about reachability.
+    pub fn draw(
+        &self,
+        context: &WebGl2RenderingContext,
+        mesh: &ElementMesh<Dist>,
+        epsilon: Dist,
+        reachable_from: Option<impl Fn(Point<Dist, 2>) -> [Dist; 2]>,
+    ) -> Result<(), String> {
+        context.use_program(Some(&self.program));
+        context.uniform1f(Some(&self.u_epsilon), nalgebra::convert(epsilon));
+        context.uniform1i(
+            Some(&self.u_show_reachable),
+            reachable_from.is_some() as i32,
+        );
+
+        // Build vertex data, duplicating shared vertices since adjacent
+        // triangles may lie in cells that are reachable from different points
+        let vertex_data = mesh
+            .iter_triangle_vertices()
+            .flat_map(|triangle| {
+                let centroid = Point::from(
+                    triangle
+                        .iter()
+                        .map(|vertex| vertex.point.coords)
+                        .sum::<Vector2<Dist>>()
+                        / 3.,
+                );
+                let reachable_from = reachable_from
+                    .as_ref()
+                    .map_or([0.; 2], |reachable_from| reachable_from(centroid))
+                    .map(|coord| {
+                        nalgebra::convert::<Dist, f32>(coord).min(f32::MAX)
+                    });
+
+                triangle.map(|vertex| FreeSpaceVertex {
+                    point: vertex.point.coords.cast::<f32>().into(),
+                    value: nalgebra::convert(vertex.value),
+                    reachable_from,
+                })
+            })
+            .collect_vec();
+
+        self.vertex_buffer.write(&vertex_data);
+
+        context.bind_vertex_array(Some(&self.vao));
+        context.draw_arrays(
+            WebGl2RenderingContext::TRIANGLES,
+            0,
+            vertex_data.len() as i32,
+        );
+
+        context.bind_vertex_array(None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+#version 300 es
+
+precision highp float;
+
+in vec2 v_point;
+in float v_value;
+flat in vec2 v_reachable_from;
+
+out vec4 out_color;
+
+uniform float u_epsilon;
+uniform bool u_show_reachable;
+
+void main() {
+    if (v_value > u_epsilon) {
+        discard;
+    }
+
+    if (!u_show_reachable) {
+        out_color = vec4(0.4, 0.7, 1.0, 0.5);
+        return;
+    }
+
+    bool reachable = v_point.x >= v_reachable_from.x
+        || v_point.y >= v_reachable_from.y;
+    out_color = reachable
+        ? vec4(0.94, 0.35, 0.16, 0.6)
+        : vec4(1.0, 1.0, 1.0, 0.4);
+}
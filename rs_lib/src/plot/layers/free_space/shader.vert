@@ -0,0 +1,18 @@
+#version 300 es
+
+in vec4 a_position;
+in float a_value;
+in vec2 a_reachable_from;
+
+out vec2 v_point;
+out float v_value;
+flat out vec2 v_reachable_from;
+
+uniform mat4 u_transform;
+
+void main() {
+    v_point = a_position.xy;
+    v_value = a_value;
+    v_reachable_from = a_reachable_from;
+    gl_Position = a_position * u_transform;
+}
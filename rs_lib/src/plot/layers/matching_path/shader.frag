@@ -0,0 +1,10 @@
+#version 300 es
+
+precision highp float;
+
+in vec4 v_color;
+out vec4 out_color;
+
+void main() {
+    out_color = v_color;
+}
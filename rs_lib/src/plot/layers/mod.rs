@@ -1,2 +1,26 @@
+use nalgebra::Matrix4;
+use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation};
+
+use crate::geom::Dist;
+
 pub mod contour_lines;
 pub mod density;
+pub mod free_space;
+pub mod matching_path;
+
+/// Uploads the transformation matrix from parameter space to clip space to the
+/// `u_transform` uniform of `program`, whose shaders multiply positions by it
+/// from the right.
+fn upload_transform(
+    context: &WebGl2RenderingContext,
+    program: &WebGlProgram,
+    u_transform: &WebGlUniformLocation,
+    mat: Matrix4<Dist>,
+) {
+    context.use_program(Some(program));
+    context.uniform_matrix4fv_with_f32_array(
+        Some(u_transform),
+        false,
+        mat.transpose().cast::<f32>().data.as_slice(),
+    );
+}
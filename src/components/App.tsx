@@ -1,8 +1,8 @@
-import { useState } from 'react';
+import { useMemo, useState } from 'react';
 
-import { JsCurve } from '@rs_lib';
+import { IPolygons, JsCurve } from '@rs_lib';
 import { CurveSpaceView } from './CurveSpaceView';
-import { ParamSpaceView } from './ParamSpaceView';
+import { ParamSpaceView, type ParameterizationKind } from './ParamSpaceView';
 
 export function App(): JSX.Element {
     const [curves, setCurves] = useState<[JsCurve, JsCurve]>([
@@ -18,6 +18,18 @@ export function App(): JSX.Element {
         ]),
     ]);
 
+    const [parameterization, setParameterization] =
+        useState<ParameterizationKind>('arc_length');
+    const parameterizedCurves = useMemo(
+        () =>
+            curves.map((curve) =>
+                curve.with_parameterization({ kind: parameterization }),
+            ) as [JsCurve, JsCurve],
+        [curves, parameterization],
+    );
+
+    const [obstacles, setObstacles] = useState<IPolygons>([]);
+
     const [highlightLeash, setHighlightLeash] = useState<
         [number, number] | null
     >(null);
@@ -25,12 +37,18 @@ export function App(): JSX.Element {
     return (
         <div className="space-view-container">
             <CurveSpaceView
-                curves={curves}
+                curves={parameterizedCurves}
                 updateCurves={setCurves}
+                obstacles={obstacles}
+                setObstacles={setObstacles}
                 highlightLeash={highlightLeash}
+                setHighlightLeash={setHighlightLeash}
             />
             <ParamSpaceView
-                curves={curves}
+                curves={parameterizedCurves}
+                obstacles={obstacles}
+                parameterization={parameterization}
+                setParameterization={setParameterization}
                 highlightLeash={highlightLeash}
                 setHighlightLeash={setHighlightLeash}
             />
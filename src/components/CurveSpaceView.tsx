@@ -4,18 +4,33 @@ import {
     Mafs,
     MovablePoint,
     Point,
+    Polygon,
     Polyline,
     Theme,
 } from 'mafs';
-import { type Dispatch, type SetStateAction, useState } from 'react';
+import { type Dispatch, type SetStateAction, useMemo, useState } from 'react';
 
-import { IPoint, JsCurve } from '@rs_lib';
+import {
+    IMeanObjective,
+    IPartialFrechet,
+    IPoint,
+    IPolygons,
+    ISimilarityCost,
+    JsCurve,
+    frechet_mean,
+    geodesic_path,
+} from '@rs_lib';
 import { useBoundingClientRect } from '../hooks/useBoundingClientRect';
 
 interface CurveSpaceViewCanvasProps {
     curves: [JsCurve, JsCurve];
     updateCurves: Dispatch<SetStateAction<JsCurve[]>>;
+    obstacles: IPolygons;
+    setObstacles: Dispatch<SetStateAction<IPolygons>>;
     highlightLeash: [number, number] | null;
+    setHighlightLeash: Dispatch<SetStateAction<[number, number] | null>>;
+    partialFrechet: IPartialFrechet | null;
+    meanCurve: IPoint[] | null;
 
     width: number;
     height: number;
@@ -23,10 +38,72 @@ interface CurveSpaceViewCanvasProps {
 
 type CurveSpaceViewProps = Pick<
     CurveSpaceViewCanvasProps,
-    'curves' | 'updateCurves' | 'highlightLeash'
+    | 'curves'
+    | 'updateCurves'
+    | 'obstacles'
+    | 'setObstacles'
+    | 'highlightLeash'
+    | 'setHighlightLeash'
 >;
 
 export function CurveSpaceView(props: CurveSpaceViewProps): JSX.Element {
+    const { curves, updateCurves, obstacles, setObstacles, setHighlightLeash } =
+        props;
+
+    const [showPartialMatch, setShowPartialMatch] = useState(false);
+    const [similarityCost, setSimilarityCost] =
+        useState<ISimilarityCost>('frechet');
+    const [similarityDist, setSimilarityDist] = useState<number | null>(null);
+
+    const hausdorff = useMemo(
+        () => curves[0].hausdorff(curves[1]) ?? null,
+        [curves],
+    );
+
+    const partialFrechet = useMemo(
+        () =>
+            showPartialMatch
+                ? curves[0].partial_frechet(curves[1]) ?? null
+                : null,
+        [curves, showPartialMatch],
+    );
+
+    const [meanObjective, setMeanObjective] = useState<
+        IMeanObjective | 'none'
+    >('none');
+    const frechetMean = useMemo(
+        () =>
+            meanObjective !== 'none' &&
+            curves.every((curve) => curve.points.length > 0)
+                ? frechet_mean(
+                      curves.map((curve) => curve.points),
+                      meanObjective,
+                      Math.max(...curves.map((curve) => curve.points.length)),
+                  ) ?? null
+                : null,
+        [curves, meanObjective],
+    );
+
+    const alignByTranslation = () => {
+        const { translation } = curves[0].translation_frechet(curves[1]);
+        updateCurves((curves) => [
+            curves[0],
+            curves[1].translated(translation),
+        ]);
+    };
+
+    const alignBySimilarity = () => {
+        const { dist, transform } = curves[0].similarity_alignment(
+            curves[1],
+            similarityCost,
+        );
+        setSimilarityDist(dist);
+        updateCurves((curves) => [
+            curves[0],
+            curves[1].transformed(transform),
+        ]);
+    };
+
     const [containerElement, setContainerElement] =
         useState<HTMLElement | null>(null);
     const containerRect = useBoundingClientRect(containerElement);
@@ -35,12 +112,137 @@ export function CurveSpaceView(props: CurveSpaceViewProps): JSX.Element {
         <div className="space-view">
             <header className="space-view__header">
                 <div className="space-view__title">Curves space</div>
+                {hausdorff !== null && (
+                    <button
+                        className="space-view__tool"
+                        title="Show the Hausdorff witness as leash"
+                        onClick={() =>
+                            setHighlightLeash(hausdorff.witness_params)
+                        }
+                    >
+                        Hausdorff distance: {hausdorff.dist.toFixed(3)}
+                    </button>
+                )}
+                {curves.every((curve) => curve.points.length > 0) && (
+                    <button
+                        className="space-view__tool"
+                        title="Translate curve 2 to best match curve 1"
+                        onClick={alignByTranslation}
+                    >
+                        Align by translation
+                    </button>
+                )}
+                {curves.every((curve) => curve.points.length > 0) && (
+                    <button
+                        className="space-view__tool"
+                        title="Rotate, scale and translate curve 2 to best match curve 1"
+                        onClick={alignBySimilarity}
+                    >
+                        Align by similarity
+                    </button>
+                )}
+                <label className="space-view__tool">
+                    under
+                    <select
+                        value={similarityCost}
+                        onChange={(e) =>
+                            setSimilarityCost(
+                                e.target.value as ISimilarityCost,
+                            )
+                        }
+                    >
+                        <option value="frechet">Fréchet</option>
+                        <option value="dtw">DTW</option>
+                    </select>
+                </label>
+                {similarityDist !== null && (
+                    <div className="space-view__stat">
+                        Residual distance: {similarityDist.toFixed(3)}
+                    </div>
+                )}
+                <button
+                    className="space-view__tool"
+                    title="Start a new obstacle, shift-click to add its points"
+                    onClick={() =>
+                        setObstacles((obstacles) => [...obstacles, []])
+                    }
+                >
+                    New obstacle
+                </button>
+                {obstacles.length > 0 && (
+                    <button
+                        className="space-view__tool"
+                        onClick={() => setObstacles([])}
+                    >
+                        Clear obstacles
+                    </button>
+                )}
+                <label
+                    className="space-view__tool"
+                    title="Connect the last point of each curve back to its first"
+                >
+                    <input
+                        type="checkbox"
+                        checked={curves.every((curve) => curve.closed)}
+                        onChange={(e) => {
+                            const closed = e.target.checked;
+                            updateCurves((curves) =>
+                                curves.map((curve) =>
+                                    curve.with_closed(closed),
+                                ),
+                            );
+                        }}
+                    />
+                    Closed curves
+                </label>
+                <label
+                    className="space-view__tool"
+                    title="Show a curve minimizing the maximum or sum of Fréchet distances to both curves"
+                >
+                    Mean curve
+                    <select
+                        value={meanObjective}
+                        onChange={(e) =>
+                            setMeanObjective(
+                                e.target.value as IMeanObjective | 'none',
+                            )
+                        }
+                    >
+                        <option value="none">None</option>
+                        <option value="center">Center</option>
+                        <option value="median">Median</option>
+                    </select>
+                </label>
+                {frechetMean !== null && (
+                    <div className="space-view__stat">
+                        Mean cost: {frechetMean.cost.toFixed(3)}
+                    </div>
+                )}
+                <label
+                    className="space-view__tool"
+                    title="Highlight the best matching subcurve of curve 2"
+                >
+                    <input
+                        type="checkbox"
+                        checked={showPartialMatch}
+                        onChange={(e) => setShowPartialMatch(e.target.checked)}
+                    />
+                    Partial match
+                </label>
+                {partialFrechet !== null && (
+                    <div className="space-view__stat">
+                        Partial Fréchet distance:{' '}
+                        {partialFrechet.dist.toFixed(3)}
+                    </div>
+                )}
             </header>
             <div ref={setContainerElement} className="space-view__canvas">
                 {containerRect && (
                     <CurveSpaceViewCanvas
                         width={containerRect.width}
                         height={containerRect.height}
+                        partialFrechet={partialFrechet}
+                        meanCurve={frechetMean?.points ?? null}
                         {...props}
                     />
                 )}
@@ -50,7 +252,17 @@ export function CurveSpaceView(props: CurveSpaceViewProps): JSX.Element {
 }
 
 function CurveSpaceViewCanvas(props: CurveSpaceViewCanvasProps): JSX.Element {
-    const { width, height, curves, updateCurves, highlightLeash } = props;
+    const {
+        width,
+        height,
+        curves,
+        updateCurves,
+        obstacles,
+        setObstacles,
+        highlightLeash,
+        partialFrechet,
+        meanCurve,
+    } = props;
 
     return (
         <Mafs
@@ -62,6 +274,14 @@ function CurveSpaceViewCanvas(props: CurveSpaceViewCanvasProps): JSX.Element {
                     return;
                 }
 
+                if (event.shiftKey) {
+                    setObstacles((obstacles) => [
+                        ...obstacles.slice(0, -1),
+                        [...(obstacles[obstacles.length - 1] ?? []), newPoint],
+                    ]);
+                    return;
+                }
+
                 const curveIdx = event.ctrlKey ? 1 : 0;
                 updateCurves((curves) => {
                     curves = [...curves];
@@ -71,6 +291,13 @@ function CurveSpaceViewCanvas(props: CurveSpaceViewCanvasProps): JSX.Element {
             }}
         >
             <Coordinates.Cartesian />
+            {obstacles.map((obstacle, obstacleIdx) => (
+                <Polygon
+                    key={obstacleIdx}
+                    points={obstacle}
+                    color={Theme.foreground}
+                />
+            ))}
             {curves.map((curve, curveIdx) => (
                 <Polyline
                     key={curveIdx}
@@ -78,8 +305,22 @@ function CurveSpaceViewCanvas(props: CurveSpaceViewCanvasProps): JSX.Element {
                     color={curveIdx === 0 ? Theme.blue : Theme.green}
                 />
             ))}
+            {meanCurve && (
+                <Polyline points={meanCurve} color={Theme.orange} />
+            )}
+            {partialFrechet && (
+                <Polyline
+                    points={subcurvePoints(
+                        curves[1],
+                        partialFrechet.start_param,
+                        partialFrechet.end_param,
+                    )}
+                    color={Theme.yellow}
+                    weight={4}
+                />
+            )}
             {curves.map((curve, curveIdx) =>
-                curve.points.map((point, pointIdx) => (
+                curve.vertices.map((point, pointIdx) => (
                     <MovablePoint
                         key={`${curveIdx}.${pointIdx}`}
                         point={point}
@@ -97,31 +338,61 @@ function CurveSpaceViewCanvas(props: CurveSpaceViewCanvasProps): JSX.Element {
                 )),
             )}
             {highlightLeash && (
-                <LeashPreview curves={curves} leash={highlightLeash} />
+                <LeashPreview
+                    curves={curves}
+                    obstacles={obstacles}
+                    leash={highlightLeash}
+                />
             )}
         </Mafs>
     );
 }
 
+/**
+ * Returns the vertices of the part of `curve` between the parameters `start`
+ * and `end`.
+ */
+function subcurvePoints(curve: JsCurve, start: number, end: number): IPoint[] {
+    const params = curve.cumulative_params;
+    const points = curve.points;
+
+    return [
+        curve.at(start) as IPoint,
+        ...points.filter((_, idx) => start < params[idx] && params[idx] < end),
+        curve.at(end) as IPoint,
+    ];
+}
+
 interface LeashPreviewProps {
     curves: [JsCurve, JsCurve];
+    obstacles: IPolygons;
     leash: [number, number];
 }
 
 function LeashPreview(props: LeashPreviewProps): JSX.Element {
-    const { curves, leash } = props;
+    const { curves, obstacles, leash } = props;
     const points = [
         curves[0].at(leash[0]) as IPoint,
         curves[1].at(leash[1]) as IPoint,
     ];
 
+    // With obstacles, the leash follows a shortest path around them
+    const path =
+        obstacles.length > 0
+            ? geodesic_path(obstacles, points[0], points[1])
+            : undefined;
+
     return (
         <>
-            <Line.Segment
-                point1={points[0]}
-                point2={points[1]}
-                color={Theme.pink}
-            />
+            {path ? (
+                <Polyline points={path} color={Theme.pink} />
+            ) : (
+                <Line.Segment
+                    point1={points[0]}
+                    point2={points[1]}
+                    color={Theme.pink}
+                />
+            )}
             {points.map(([x, y], curveIdx) => (
                 <Point key={curveIdx} x={x} y={y} color={Theme.pink} />
             ))}
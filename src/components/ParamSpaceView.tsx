@@ -14,36 +14,199 @@ import {
     Line,
     Mafs,
     MovablePoint,
+    Point,
+    Polyline,
     Theme,
     usePaneContext,
     useTransformContext,
     vec,
 } from 'mafs';
 
-import { ILengths, JsCurve, Plotter } from '@rs_lib';
+import {
+    IAlignment,
+    ILengths,
+    IParameterization,
+    IPointMetric,
+    IPoints,
+    IPolygons,
+    JsCurve,
+    Plotter,
+} from '@rs_lib';
 
 import { useBoundingClientRect } from '../hooks/useBoundingClientRect';
 import { useDevicePixelRatio } from '../hooks/useDevicePixelRatio';
 
+type AlignmentMeasure = 'none' | 'lcss' | 'edr' | 'erp';
+
+// Timestamps can't be drawn, so they can only be attached from code
+export type ParameterizationKind = Exclude<
+    IParameterization['kind'],
+    'timestamps'
+>;
+
 interface ParamSpaceViewCanvasProps {
     width: number;
     height: number;
     showMesh: boolean;
+    showCoupling: boolean;
+    showDtwPath: boolean;
+    showMatchingPath: boolean;
+    freeSpaceEpsilon: number | null;
+    integralPath: IPoints | null;
+    closedMatching: IPoints | null;
+    alignmentPairs: IAlignment['pairs'] | null;
+    setMatchingPath: Dispatch<SetStateAction<IPoints | null>>;
+    metric: IPointMetric;
 
     curves: [JsCurve, JsCurve];
+    obstacles: IPolygons;
     highlightLeash: [number, number] | null;
     setHighlightLeash: Dispatch<SetStateAction<[number, number] | null>>;
 }
 
 type ParamSpaceViewProps = Pick<
     ParamSpaceViewCanvasProps,
-    'curves' | 'highlightLeash' | 'setHighlightLeash'
->;
+    'curves' | 'obstacles' | 'highlightLeash' | 'setHighlightLeash'
+> & {
+    parameterization: ParameterizationKind;
+    setParameterization: Dispatch<SetStateAction<ParameterizationKind>>;
+};
 
 export function ParamSpaceView(props: ParamSpaceViewProps): JSX.Element {
-    const { curves, ...otherProps } = props;
+    const {
+        curves,
+        obstacles,
+        parameterization,
+        setParameterization,
+        ...otherProps
+    } = props;
 
+    const [showFrechetDist, setShowFrechetDist] = useState(false);
+    const [showClosedFrechet, setShowClosedFrechet] = useState(false);
+    const [showGeodesicFrechet, setShowGeodesicFrechet] = useState(false);
     const [showMesh, setShowMesh] = useState(false);
+    const [showCoupling, setShowCoupling] = useState(false);
+    const [showDtwPath, setShowDtwPath] = useState(false);
+    const [showMatchingPath, setShowMatchingPath] = useState(false);
+    const [matchingPath, setMatchingPath] = useState<IPoints | null>(null);
+    const [showFreeSpace, setShowFreeSpace] = useState(false);
+    const [freeSpaceEpsilon, setFreeSpaceEpsilon] = useState(1);
+    const [showIntegralPath, setShowIntegralPath] = useState(false);
+    const [alignmentMeasure, setAlignmentMeasure] =
+        useState<AlignmentMeasure>('none');
+    const [alignmentEpsilon, setAlignmentEpsilon] = useState(0.5);
+    const [metricKind, setMetricKind] =
+        useState<IPointMetric['kind']>('euclidean');
+    const [metricWeightY, setMetricWeightY] = useState(4);
+
+    // The matching path is the one for straight Euclidean leashes, so it is
+    // only offered when those are plotted
+    const euclideanLeashes =
+        metricKind === 'euclidean' && obstacles.length === 0;
+    const matchingPathShown = showMatchingPath && euclideanLeashes;
+
+    const metric = useMemo<IPointMetric>(
+        () =>
+            metricKind === 'weighted'
+                ? { kind: metricKind, weights: [1, metricWeightY] }
+                : { kind: metricKind },
+        [metricKind, metricWeightY],
+    );
+
+    // Enumerating the critical values is too slow to repeat on every edit of
+    // long curves unless the distance is actually shown
+    const frechetDist = useMemo(
+        () =>
+            showFrechetDist
+                ? curves[0].frechet_dist(curves[1]) ?? null
+                : null,
+        [curves, showFrechetDist],
+    );
+
+    // Closed curves are compared over all start points of curve 2, which
+    // evaluates the Fréchet distance many times, so only do so when shown
+    const closedFrechet = useMemo(
+        () =>
+            showClosedFrechet &&
+            curves[1].closed &&
+            curves.every((curve) => curve.points.length > 0)
+                ? curves[0].closed_frechet(curves[1])
+                : null,
+        [curves, showClosedFrechet],
+    );
+
+    // Shortest paths around the obstacles are computed between every pair of
+    // samples, so the approximation only runs when shown
+    const geodesicFrechet = useMemo(() => {
+        if (
+            !showGeodesicFrechet ||
+            obstacles.length === 0 ||
+            !curves.every((curve) => curve.points.length > 0)
+        ) {
+            return null;
+        }
+
+        // Sample spacing relative to the longer curve
+        const totalLengths = curves.map((curve) => {
+            const lengths = curve.cumulative_lengths;
+            return lengths[lengths.length - 1];
+        });
+        const resolution = Math.max(...totalLengths) / 64;
+        return curves[0].geodesic_frechet(
+            curves[1],
+            obstacles,
+            resolution || 1,
+        );
+    }, [curves, obstacles, showGeodesicFrechet]);
+
+    const integralFrechet = useMemo(() => {
+        if (!showIntegralPath) {
+            return null;
+        }
+
+        // Grid spacing relative to the longer curve
+        const totalLengths = curves.map((curve) => {
+            const lengths = curve.cumulative_lengths;
+            return lengths[lengths.length - 1];
+        });
+        const resolution = Math.max(...totalLengths) / 64;
+        return (
+            curves[0].integral_frechet(curves[1], resolution || 1) ?? null
+        );
+    }, [curves, showIntegralPath]);
+
+    const alignment = useMemo(() => {
+        if (!curves.every((curve) => curve.points.length > 0)) {
+            return null;
+        }
+        switch (alignmentMeasure) {
+            case 'lcss':
+                return curves[0].lcss(curves[1], alignmentEpsilon);
+            case 'edr':
+                return curves[0].edr(curves[1], alignmentEpsilon);
+            case 'erp':
+                return curves[0].erp(curves[1], [0, 0]);
+            default:
+                return null;
+        }
+    }, [curves, alignmentMeasure, alignmentEpsilon]);
+
+    const animateLeash = () => {
+        if (matchingPath === null) {
+            return;
+        }
+
+        const duration = 2000; // milliseconds
+        const startTime = performance.now();
+        const step = (time: number) => {
+            const fraction = Math.min(1, (time - startTime) / duration);
+            props.setHighlightLeash(pointAlongPolyline(matchingPath, fraction));
+            if (fraction < 1) {
+                requestAnimationFrame(step);
+            }
+        };
+        requestAnimationFrame(step);
+    };
 
     const [containerElement, setContainerElement] =
         useState<HTMLElement | null>(null);
@@ -53,6 +216,101 @@ export function ParamSpaceView(props: ParamSpaceViewProps): JSX.Element {
         <div className="space-view">
             <header className="space-view__header">
                 <div className="space-view__title">Parameter space</div>
+                <label className="space-view__tool">
+                    <input
+                        type="checkbox"
+                        checked={showFrechetDist}
+                        onChange={(e) => setShowFrechetDist(e.target.checked)}
+                    />
+                    Fréchet distance
+                </label>
+                {frechetDist !== null && (
+                    <div className="space-view__stat">
+                        Fréchet distance: {frechetDist.toFixed(3)}
+                    </div>
+                )}
+                {obstacles.length > 0 && (
+                    <label className="space-view__tool">
+                        <input
+                            type="checkbox"
+                            checked={showGeodesicFrechet}
+                            onChange={(e) =>
+                                setShowGeodesicFrechet(e.target.checked)
+                            }
+                        />
+                        Geodesic Fréchet distance
+                    </label>
+                )}
+                {geodesicFrechet !== null && (
+                    <div className="space-view__stat">
+                        Geodesic Fréchet distance (sampled): ≈
+                        {geodesicFrechet.toFixed(3)}
+                    </div>
+                )}
+                {curves[1].closed && (
+                    <label className="space-view__tool">
+                        <input
+                            type="checkbox"
+                            checked={showClosedFrechet}
+                            onChange={(e) =>
+                                setShowClosedFrechet(e.target.checked)
+                            }
+                        />
+                        Closed Fréchet distance
+                    </label>
+                )}
+                {closedFrechet !== null && (
+                    <div className="space-view__stat">
+                        Closed Fréchet distance:{' '}
+                        {closedFrechet.dist.toFixed(3)} (at least{' '}
+                        {closedFrechet.lower_bound.toFixed(3)})
+                    </div>
+                )}
+                <label className="space-view__tool">
+                    Parameter
+                    <select
+                        value={parameterization}
+                        onChange={(e) =>
+                            setParameterization(
+                                e.target.value as ParameterizationKind,
+                            )
+                        }
+                    >
+                        <option value="arc_length">Arc length</option>
+                        <option value="vertex_index">Vertex index</option>
+                        <option value="normalized">Normalized</option>
+                    </select>
+                </label>
+                <label className="space-view__tool">
+                    Metric
+                    <select
+                        value={metricKind}
+                        onChange={(e) =>
+                            setMetricKind(
+                                e.target.value as IPointMetric['kind'],
+                            )
+                        }
+                    >
+                        <option value="euclidean">Euclidean</option>
+                        <option value="manhattan">Manhattan (L1)</option>
+                        <option value="chebyshev">Chebyshev (L∞)</option>
+                        <option value="weighted">Weighted</option>
+                    </select>
+                </label>
+                {metricKind === 'weighted' && (
+                    <label className="space-view__tool">
+                        y weight
+                        <input
+                            type="number"
+                            min={0}
+                            step={0.5}
+                            value={metricWeightY}
+                            onChange={(e) =>
+                                setMetricWeightY(e.target.valueAsNumber)
+                            }
+                        />
+                    </label>
+                )}
                 <label className="space-view__tool">
                     <input
                         type="checkbox"
@@ -61,6 +319,110 @@ export function ParamSpaceView(props: ParamSpaceViewProps): JSX.Element {
                     />
                     Show mesh
                 </label>
+                <label className="space-view__tool">
+                    <input
+                        type="checkbox"
+                        checked={showCoupling}
+                        onChange={(e) => setShowCoupling(e.target.checked)}
+                    />
+                    Discrete coupling
+                </label>
+                <label className="space-view__tool">
+                    <input
+                        type="checkbox"
+                        checked={showDtwPath}
+                        onChange={(e) => setShowDtwPath(e.target.checked)}
+                    />
+                    DTW path
+                </label>
+                {euclideanLeashes && (
+                    <label className="space-view__tool">
+                        <input
+                            type="checkbox"
+                            checked={showMatchingPath}
+                            onChange={(e) =>
+                                setShowMatchingPath(e.target.checked)
+                            }
+                        />
+                        Matching path
+                    </label>
+                )}
+                <label className="space-view__tool">
+                    <input
+                        type="checkbox"
+                        checked={showFreeSpace}
+                        onChange={(e) => setShowFreeSpace(e.target.checked)}
+                    />
+                    Free space
+                </label>
+                {showFreeSpace && (
+                    <label className="space-view__tool">
+                        ε
+                        <input
+                            type="number"
+                            min={0}
+                            step={0.1}
+                            value={freeSpaceEpsilon}
+                            onChange={(e) =>
+                                setFreeSpaceEpsilon(e.target.valueAsNumber)
+                            }
+                        />
+                    </label>
+                )}
+                <label className="space-view__tool">
+                    <input
+                        type="checkbox"
+                        checked={showIntegralPath}
+                        onChange={(e) => setShowIntegralPath(e.target.checked)}
+                    />
+                    Integral path
+                </label>
+                <label className="space-view__tool">
+                    Alignment
+                    <select
+                        value={alignmentMeasure}
+                        onChange={(e) =>
+                            setAlignmentMeasure(
+                                e.target.value as AlignmentMeasure,
+                            )
+                        }
+                    >
+                        <option value="none">None</option>
+                        <option value="lcss">LCSS</option>
+                        <option value="edr">EDR</option>
+                        <option value="erp">ERP</option>
+                    </select>
+                </label>
+                {(alignmentMeasure === 'lcss' ||
+                    alignmentMeasure === 'edr') && (
+                    <label className="space-view__tool">
+                        ε
+                        <input
+                            type="number"
+                            min={0}
+                            step={0.1}
+                            value={alignmentEpsilon}
+                            onChange={(e) =>
+                                setAlignmentEpsilon(e.target.valueAsNumber)
+                            }
+                        />
+                    </label>
+                )}
+                {alignment !== null && (
+                    <div className="space-view__stat">
+                        Score: {alignment.score.toFixed(3)}
+                    </div>
+                )}
+                {integralFrechet !== null && (
+                    <div className="space-view__stat">
+                        Average Fréchet: {integralFrechet.average.toFixed(3)}
+                    </div>
+                )}
+                {matchingPathShown && matchingPath !== null && (
+                    <button className="space-view__tool" onClick={animateLeash}>
+                        Animate leash
+                    </button>
+                )}
             </header>
             <div ref={setContainerElement} className="space-view__canvas">
                 {containerRect &&
@@ -69,7 +431,19 @@ export function ParamSpaceView(props: ParamSpaceViewProps): JSX.Element {
                             width={containerRect.width}
                             height={containerRect.height}
                             curves={curves}
+                            obstacles={obstacles}
                             showMesh={showMesh}
+                            showCoupling={showCoupling}
+                            showDtwPath={showDtwPath}
+                            showMatchingPath={matchingPathShown}
+                            freeSpaceEpsilon={
+                                showFreeSpace ? freeSpaceEpsilon : null
+                            }
+                            integralPath={integralFrechet?.path ?? null}
+                            closedMatching={closedFrechet?.matching ?? null}
+                            alignmentPairs={alignment?.pairs ?? null}
+                            setMatchingPath={setMatchingPath}
+                            metric={metric}
                             {...otherProps}
                         />
                     )}
@@ -83,18 +457,47 @@ function ParamSpaceViewCanvas(props: ParamSpaceViewCanvasProps): JSX.Element {
         width,
         height,
         curves,
+        obstacles,
         showMesh,
+        showCoupling,
+        showDtwPath,
+        showMatchingPath,
+        freeSpaceEpsilon,
+        integralPath,
+        closedMatching,
+        alignmentPairs,
+        setMatchingPath,
+        metric,
         highlightLeash,
         setHighlightLeash,
     } = props;
 
-    const cumulativeLengths = curves.map(
-        (curve) => curve.cumulative_lengths,
+    // A closed curve 2 is plotted over the doubled parameter domain, in which
+    // matchings can start anywhere along it
+    const plotCurves = useMemo<[JsCurve, JsCurve]>(
+        () => (curves[1].closed ? [curves[0], curves[1].doubled()] : curves),
+        [curves],
+    );
+
+    const cumulativeParams = plotCurves.map(
+        (curve) => curve.cumulative_params,
     ) as [ILengths, ILengths];
-    const totalLengths = cumulativeLengths.map(
+    const totalLengths = cumulativeParams.map(
         (lengths) => lengths[lengths.length - 1],
     ) as [number, number];
 
+    const discreteCoupling = useMemo(
+        () =>
+            showCoupling
+                ? curves[0].discrete_frechet(curves[1]).coupling
+                : null,
+        [curves, showCoupling],
+    );
+    const dtwPath = useMemo(
+        () => (showDtwPath ? curves[0].dtw(curves[1], {})?.path : null),
+        [curves, showDtwPath],
+    );
+
     const setHighlightLeashClamped = (point) => {
         setHighlightLeash([
             Math.max(0, Math.min(totalLengths[0], point[0])),
@@ -111,10 +514,33 @@ function ParamSpaceViewCanvas(props: ParamSpaceViewCanvasProps): JSX.Element {
         >
             <Coordinates.Cartesian />
             <HeightPlot
-                curves={curves}
+                curves={plotCurves}
+                obstacles={obstacles}
+                metric={metric}
                 totalLengths={totalLengths}
                 showMesh={showMesh}
+                showMatchingPath={showMatchingPath}
+                freeSpaceEpsilon={freeSpaceEpsilon}
+                setMatchingPath={setMatchingPath}
             />
+            {discreteCoupling && (
+                <Polyline points={discreteCoupling} color={Theme.orange} />
+            )}
+            {dtwPath && <Polyline points={dtwPath} color={Theme.violet} />}
+            {integralPath && (
+                <Polyline points={integralPath} color={Theme.red} />
+            )}
+            {closedMatching && (
+                <Polyline points={closedMatching} color={Theme.yellow} />
+            )}
+            {alignmentPairs?.map(([i, j], idx) => (
+                <Point
+                    key={idx}
+                    x={cumulativeParams[0][i]}
+                    y={cumulativeParams[1][j]}
+                    color={Theme.indigo}
+                />
+            ))}
             {highlightLeash && (
                 <>
                     <Line.Segment
@@ -138,6 +564,29 @@ function ParamSpaceViewCanvas(props: ParamSpaceViewCanvasProps): JSX.Element {
     );
 }
 
+function pointAlongPolyline(
+    points: IPoints,
+    fraction: number,
+): [number, number] {
+    const segmentLengths = points
+        .slice(1)
+        .map((point, idx) => vec.dist(points[idx], point));
+    const totalLength = segmentLengths.reduce((sum, length) => sum + length, 0);
+
+    let remainingLength = fraction * totalLength;
+    for (const [idx, length] of segmentLengths.entries()) {
+        if (remainingLength <= length && length > 0) {
+            return vec.lerp(
+                points[idx],
+                points[idx + 1],
+                remainingLength / length,
+            );
+        }
+        remainingLength -= length;
+    }
+    return points[points.length - 1];
+}
+
 function useClampedRange(range: Interval, clamp: Interval): Interval {
     const clampedRange: Interval = [
         Math.max(range[0], clamp[0]),
@@ -166,12 +615,26 @@ function useTransformPoint(): (point: vec.Vector2) => vec.Vector2 {
 
 interface HeightPlotProps {
     curves: [JsCurve, JsCurve];
+    obstacles: IPolygons;
+    metric: IPointMetric;
     totalLengths: [number, number];
     showMesh: boolean;
+    showMatchingPath: boolean;
+    freeSpaceEpsilon: number | null;
+    setMatchingPath: Dispatch<SetStateAction<IPoints | null>>;
 }
 
 function HeightPlot(props: HeightPlotProps) {
-    const { curves, totalLengths, showMesh } = props;
+    const {
+        curves,
+        obstacles,
+        metric,
+        totalLengths,
+        showMesh,
+        showMatchingPath,
+        freeSpaceEpsilon,
+        setMatchingPath,
+    } = props;
 
     const foreignObject = useRef<SVGForeignObjectElement>(null);
     const [canvas, setCanvas] = useState<HTMLCanvasElement | null>(null);
@@ -222,6 +685,42 @@ function HeightPlot(props: HeightPlotProps) {
         setPlotter(new Plotter(ctx));
     }, [canvas]);
 
+    // Update curves
+    useLayoutEffect(() => {
+        if (plotter === null) {
+            return;
+        }
+
+        plotter.update_curves(...curves);
+    }, [plotter, curves]);
+
+    // Fetch the matching only while it is shown, since computing it is costly
+    useLayoutEffect(() => {
+        if (plotter === null) {
+            return;
+        }
+
+        setMatchingPath(showMatchingPath ? plotter.matching_path : null);
+    }, [plotter, curves, showMatchingPath, setMatchingPath]);
+
+    // Update obstacles
+    useLayoutEffect(() => {
+        if (plotter === null) {
+            return;
+        }
+
+        plotter.set_obstacles(obstacles);
+    }, [plotter, obstacles]);
+
+    // Update metric
+    useLayoutEffect(() => {
+        if (plotter === null) {
+            return;
+        }
+
+        plotter.set_metric(metric);
+    }, [plotter, metric]);
+
     // Re-draw canvas
     useLayoutEffect(() => {
         if (plotter === null) {
@@ -231,9 +730,12 @@ function HeightPlot(props: HeightPlotProps) {
             return;
         }
 
-        plotter.update_curves(...curves);
         plotter.draw({
             show_mesh: showMesh,
+            show_matching_path: showMatchingPath,
+            free_space_epsilon: Number.isFinite(freeSpaceEpsilon)
+                ? freeSpaceEpsilon
+                : undefined,
             x_bounds: xRange,
             y_bounds: yRange,
             x_scale: scaleX,
@@ -245,7 +747,11 @@ function HeightPlot(props: HeightPlotProps) {
     }, [
         plotter,
         curves,
+        obstacles,
+        metric,
         showMesh,
+        showMatchingPath,
+        freeSpaceEpsilon,
         xRange,
         yRange,
         scaleX,